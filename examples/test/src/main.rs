@@ -27,8 +27,22 @@ fn main() {
             fullscreen: true,
             grab_cursor: true,
             show_cursor: false,
+            allow_ime: false,
         };
 
+        // actions give inputs names, so gameplay code below never has to know which keys are bound to what
+        let actions = &mut ren.input.actions;
+        actions.bind("quit", Key::Q);
+        actions.bind("quit", Binding::chord([Key::Ctrl], Key::C));
+        actions.bind_axis("move_x", AxisBinding::buttons(Key::A, Key::D));
+        actions.bind_axis("move_y", AxisBinding::buttons(Key::Shift, Key::Space));
+        actions.bind_axis("move_z", AxisBinding::buttons(Key::W, Key::S));
+        actions.bind_axis("look_x", AxisBinding::MouseX { scale: 0.01 });
+        actions.bind_axis("look_y", AxisBinding::MouseY { scale: -0.01 });
+
+        // meshes are uploaded to the GPU by the renderer, and can be updated or removed later using the returned handle
+        ren.meshes.add(pentagon());
+
         // available because of the "ecs" feature
         ren.world.spawn((Vec3::new(0., 0., 0.), ObjType::Player));
         ren.world.spawn((Vec3::new(0., 0., 0.), ObjType::Entity));
@@ -38,26 +52,30 @@ fn main() {
 
     // RenoirApp::run is run every frame
     game.run(move |ren| {
-        if (ren.input.pressed(Key::C) && ren.input.pressed(Key::Ctrl)) || ren.input.pressed(Key::Q)
-        {
+        if ren.input.action_pressed("quit") {
             ren.close();
         }
 
-        player.x += ((ren.input.pressed(Key::D) as i32 - ren.input.pressed(Key::A) as i32) as f32)
-            * speed
-            * ren.time.delta_time();
-        player.y += ((ren.input.pressed(Key::Space) as i32 - ren.input.pressed(Key::Shift) as i32)
-            as f32)
-            * speed
-            * ren.time.delta_time();
-        player.z -= ((ren.input.pressed(Key::W) as i32 - ren.input.pressed(Key::S) as i32) as f32)
-            * speed
-            * ren.time.delta_time();
-
-        let mouse_move = ren.input.get_mouse_delta();
-
-        ren.camera.rotate_y(mouse_move.0 / 100.0);
-        ren.camera.rotate_x(-mouse_move.1 / 100.0);
+        player.x += ren.input.axis("move_x") * speed * ren.time.delta_time();
+        player.y += ren.input.axis("move_y") * speed * ren.time.delta_time();
+        player.z += ren.input.axis("move_z") * speed * ren.time.delta_time();
+
+        ren.camera.rotate_y(ren.input.axis("look_x"));
+        ren.camera.rotate_x(ren.input.axis("look_y"));
         ren.camera.set_translate(player.x, player.y, player.z);
     })
 }
+
+#[rustfmt::skip]
+fn pentagon() -> Mesh {
+    Mesh::new(
+        vec![
+            Vertex { position: Vec3::new(-0.0868241, 0.49240386, 0.0),   tex_coords: Vec2::new(0.4131759, 0.00759614) },
+            Vertex { position: Vec3::new(-0.49513406, 0.06958647, 0.0),  tex_coords: Vec2::new(0.0048659444, 0.43041354) },
+            Vertex { position: Vec3::new(-0.21918549, -0.44939706, 0.0), tex_coords: Vec2::new(0.28081453, 0.949397) },
+            Vertex { position: Vec3::new(0.35966998, -0.3473291, 0.0),   tex_coords: Vec2::new(0.85967, 0.84732914) },
+            Vertex { position: Vec3::new(0.44147372, 0.2347359, 0.0),    tex_coords: Vec2::new(0.9414737, 0.2652641) },
+        ],
+        vec![0, 1, 4, 1, 2, 4, 2, 3, 4],
+    )
+}
@@ -1,4 +1,6 @@
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 use winit::{
     application::ApplicationHandler,
     event::{DeviceEvent, WindowEvent},
@@ -8,6 +10,7 @@ use winit::{
 
 use crate::render::renderer::Renderer;
 use crate::state::app_state::RenoirAppState;
+use crate::state::input::InputRecording;
 
 // these types are only used here, and only one of each can exist in the entire program, so idc if it's complex
 #[allow(clippy::type_complexity)]
@@ -17,8 +20,13 @@ pub struct RenoirApp {
     window: Option<Arc<Window>>,
     state: RenoirAppState,
     run_fn: Option<Box<dyn FnMut(&mut RenoirAppState)>>,
+    fixed_fn: Option<Box<dyn FnMut(&mut RenoirAppState)>>,
     setup_fn: Option<Box<dyn FnMut(&mut RenoirAppState)>>,
     quit_fn: Option<Box<dyn FnMut(&mut RenoirAppState)>>,
+    // where the input recording is written to as the app runs, see record_input
+    input_recording_path: Option<PathBuf>,
+    // what WindowOptions::allow_ime was last applied to the window as
+    ime_allowed: Option<bool>,
 }
 
 impl RenoirApp {
@@ -27,8 +35,11 @@ impl RenoirApp {
             renderer: None,
             window: None,
             run_fn: None,
+            fixed_fn: None,
             setup_fn: None,
             quit_fn: None,
+            input_recording_path: None,
+            ime_allowed: None,
             state: RenoirAppState::new(),
         }
     }
@@ -41,6 +52,27 @@ impl RenoirApp {
         self.quit_fn = Some(Box::new(quit_fn));
     }
 
+    /// Sets a function that runs at a fixed rate (60 Hz by default, see DeltaTime::set_fixed_rate) rather than once
+    /// per frame, which keeps physics and gameplay deterministic. It runs before the main function, as many times as
+    /// needed to catch up with the time that has passed.
+    pub fn fixed_update(&mut self, fixed_fn: impl FnMut(&mut RenoirAppState) + 'static) {
+        self.fixed_fn = Some(Box::new(fixed_fn));
+    }
+
+    /// Records all input and frame times from the first frame on into `path`. Every frame is written as soon as it's
+    /// done, so the recording survives the app crashing. Play it back with `play_input` to reproduce the session,
+    /// e.g. for bug reports or regression tests.
+    pub fn record_input(&mut self, path: impl Into<PathBuf>) {
+        self.input_recording_path = Some(path.into());
+    }
+
+    /// Replays a recording made with `record_input` instead of reading live input. Each frame gets the recorded
+    /// events and the recorded delta time, so a deterministic game plays out exactly like it did when it was recorded.
+    /// Once the recording runs out, the app goes back to live input.
+    pub fn play_input(&mut self, recording: InputRecording) {
+        self.state.input.start_playback(recording);
+    }
+
     pub fn run(&mut self, run_fn: impl FnMut(&mut RenoirAppState) + 'static) {
         let event_loop = EventLoop::new().unwrap();
 
@@ -50,6 +82,21 @@ impl RenoirApp {
         self.run_fn = Some(Box::new(run_fn));
         env_logger::init();
 
+        // started here rather than in record_input, so that there's a logger to report a file that can't be created
+        if let Some(path) = &self.input_recording_path {
+            match self.state.input.start_recording_to(path) {
+                Ok(_) => log::info!("recording input to {}", path.display()),
+                Err(e) => log::error!("{e:#}"),
+            }
+        }
+
+        // real controllers, a backend that fails to start just means no gamepads
+        #[cfg(feature = "gilrs")]
+        match crate::state::input::GilrsBackend::new() {
+            Ok(backend) => self.state.input.add_gamepad_backend(backend),
+            Err(e) => log::warn!("{e:#}"),
+        }
+
         match event_loop.run_app(self) {
             Ok(_) => {
                 println!("See you next time...") // i miss osu
@@ -60,6 +107,15 @@ impl RenoirApp {
 }
 
 impl ApplicationHandler for RenoirApp {
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        // every frame has already been written, this just closes the file
+        if let Some(path) = self.input_recording_path.take() {
+            if self.state.input.stop_recording().is_some() {
+                log::info!("saved input recording to {}", path.display());
+            }
+        }
+    }
+
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         // I owe my life to this file: https://github.com/nical/lyon/blob/main/examples/wgpu/src/main.rs
         let window = Arc::new(
@@ -69,7 +125,14 @@ impl ApplicationHandler for RenoirApp {
         );
 
         // apply all changes the game dev has made to window settings before running
-        self.state.window_options.apply_to(&window);
+        self.ime_allowed = None;
+        self.state
+            .window_options
+            .apply_to(&window, &mut self.ime_allowed);
+
+        self.state.window.set_size(window.inner_size());
+        self.state.window.set_scale_factor(window.scale_factor());
+        self.state.input.set_scale_factor(window.scale_factor());
 
         let renderer = Renderer::new(Arc::clone(&window));
 
@@ -83,6 +146,11 @@ impl ApplicationHandler for RenoirApp {
         _device_id: winit::event::DeviceId,
         event: winit::event::DeviceEvent,
     ) {
+        // live input is ignored while a recording is being played back
+        if self.state.input.is_playing_back() {
+            return;
+        }
+
         match event {
             DeviceEvent::MouseMotion { delta } => self
                 .state
@@ -109,14 +177,41 @@ impl ApplicationHandler for RenoirApp {
                 event_loop.exit();
             }
 
+            WindowEvent::Resized(size) => {
+                if let Some(renderer) = self.renderer.as_mut() {
+                    renderer.resize(size);
+                }
+                self.state.window.set_size(size);
+            }
+
+            // winit usually sends a Resized event right after this, but the new size is read here anyway in case it doesn't
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.state.window.set_scale_factor(scale_factor);
+                self.state.input.set_scale_factor(scale_factor);
+
+                if let Some(window) = self.window.as_ref() {
+                    let size = window.inner_size();
+                    if let Some(renderer) = self.renderer.as_mut() {
+                        renderer.resize(size);
+                    }
+                    self.state.window.set_size(size);
+                }
+            }
+
             // Renoir uses a custom input system so that users don't have to deal with handling WindowEvents,
             // it reads all inputs before running the main game loop and processes them with the RenoirInput struct
             // i havent encountered this @ syntax often so brief explanation: it matches the whole enum variant instead of destructuring into fields.
             // this also lets me match against several variants which is handy for simplifying this main loop
             event @ (WindowEvent::ModifiersChanged { .. }
             | WindowEvent::KeyboardInput { .. }
-            | WindowEvent::MouseInput { .. }) => {
-                self.state.input.process_events(event);
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::CursorMoved { .. }
+            | WindowEvent::CursorEntered { .. }
+            | WindowEvent::CursorLeft { .. }
+            | WindowEvent::Ime(..)) => {
+                if !self.state.input.is_playing_back() {
+                    self.state.input.process_events(event);
+                }
             }
 
             WindowEvent::RedrawRequested => {
@@ -126,36 +221,77 @@ impl ApplicationHandler for RenoirApp {
                     self.setup_fn = None;
                 }
 
-                // update delta time
-                self.state.time.update();
+                // update delta time, either from the clock or from the input recording being played back
+                match self.state.input.play_frame() {
+                    Some(delta_time) => self.state.time.replay(delta_time),
+                    None => {
+                        // gamepads aren't window events, so they're read here instead
+                        self.state.input.poll_gamepads();
+                        self.state.time.update();
+                    }
+                }
+                self.state
+                    .input
+                    .record_frame(self.state.time.unscaled_delta_time());
+                self.state
+                    .input
+                    .set_time(self.state.time.unscaled_elapsed());
+                self.state
+                    .stats
+                    .record_frame_time(self.state.time.raw_delta_time());
+
+                // run the fixed update as many times as the time since the last frame calls for
+                let fixed_steps = self.state.time.fixed_steps();
+                if let Some(fixed_fn) = self.fixed_fn.as_mut() {
+                    for _ in 0..fixed_steps {
+                        fixed_fn(&mut self.state);
+                    }
+                }
 
                 // Run the user's main function
                 // unwrapping is safe here as a RedrawRequested event cannot happen before the developer specifies a run_fn when calling RenoirApp::run()
+                let run_start = Instant::now();
                 self.run_fn.as_mut().unwrap()(&mut self.state);
+                self.state.stats.record_run_time(run_start.elapsed());
 
                 // if after running the main function the developer has decided the application should close, close it.
                 if self.state.flow.should_close() {
                     event_loop.exit()
                 }
 
+                // the camera modes read input, so this has to happen before it's updated
+                let delta_time = self.state.time.unscaled_delta_time();
+                self.state.camera.update_mode(&self.state.input, delta_time);
+                for (_, camera) in self.state.cameras.iter_mut() {
+                    camera.update_mode(&self.state.input, delta_time);
+                }
+
                 // TODO: check if settings have changed before reapplying
                 // apply WindowOptions to Window
                 self.state
                     .window_options
-                    .apply_to(self.window.as_ref().unwrap());
-
-                // TODO: this is producing multiple inputs for a held key, which may be the desired behavior but I need to think it through
-                if !self.state.input.key_stream.is_empty() {
-                    //dbg!(&self.state.input.key_stream);
-                }
+                    .apply_to(self.window.as_ref().unwrap(), &mut self.ime_allowed);
 
                 // update the input struct, done after running the user's main function so that we don't unset keys before we need to.
                 self.state.input.update();
+                self.state.window.update();
 
                 // this should always unwrap, as RedrawRequested only happens after the renderer has been initialized.
                 // additionally, destructuring in order to get a single mutable reference makes this whole thing simpler
                 if let Some(renderer) = self.renderer.as_mut() {
-                    match renderer.render(&mut self.state.camera) {
+                    if self.state.capture.wants_frame() {
+                        renderer.capture_next_frame();
+                    }
+
+                    let render_start = Instant::now();
+                    let result = renderer.render(
+                        &mut self.state.camera,
+                        &mut self.state.cameras,
+                        &mut self.state.meshes,
+                    );
+                    self.state.stats.record_render_time(render_start.elapsed());
+
+                    match result {
                         Ok(_) => {}
                         // Reconfigure the surface if lost
                         Err(wgpu::SurfaceError::Lost) => renderer.resize(renderer.size),
@@ -164,6 +300,12 @@ impl ApplicationHandler for RenoirApp {
                         // All other errors (Outdated, Timeout) should be resolved by the next frame
                         Err(e) => eprintln!("{:?}", e),
                     }
+
+                    match renderer.take_captured_frame() {
+                        Some(Ok(frame)) => self.state.capture.save(frame),
+                        Some(Err(e)) => self.state.capture.fail(e),
+                        None => {}
+                    }
                 } else {
                     println!("Renderer wasn't initialized prior to trying to render.. ??");
                 }
@@ -6,8 +6,30 @@ mod state;
 pub mod prelude {
     pub use crate::app::RenoirApp;
     pub use crate::math::prelude::*;
+    pub use crate::render::mesh::{Mesh, MeshHandle, Meshes};
+    pub use crate::render::model::{Material, MaterialTexture, Model, ModelMesh};
+    pub use crate::render::renderer::Renderer;
+    pub use crate::render::scene::{Scene, SceneCamera, SceneNode, SceneProjection};
+    pub use crate::render::vertex::Vertex;
     pub use crate::state::app_state::RenoirAppState;
-    pub use crate::state::camera::TransformSpace;
-    pub use crate::state::input::{Key, MouseBtn};
+    pub use crate::state::camera::{
+        CameraController, CameraHandle, CameraMode, Cameras, FlyCamera, FollowCamera, OrbitCamera,
+        OrthographicSize, Projection, Ray, TransformSpace, Viewport,
+    };
+    pub use crate::state::capture::FrameCapture;
+    pub use crate::state::frame_stats::{FrameStats, TimingSamples};
+    pub use crate::state::input::{
+        ActionMap, AxisBinding, Binding, BindingConflict, Combo, GamepadAxis, GamepadBackend,
+        GamepadButton, GamepadEvent, GamepadId, InputContext, InputEvent, InputRecording, Key,
+        KeyState, LogicalKey, MouseBtn, RecordedFrame, RenoirInput, VirtualGamepad,
+    };
+    pub use crate::state::time::{Clock, DeltaTime, ManualClock, SystemClock};
+    pub use crate::state::window_info::WindowInfo;
     pub use crate::state::window_options::WindowOptions;
+
+    #[cfg(feature = "ecs")]
+    pub use crate::render::scene::{Children, NodeMeshes, NodeName, NodeTransform, Parent};
+
+    #[cfg(feature = "gilrs")]
+    pub use crate::state::input::GilrsBackend;
 }
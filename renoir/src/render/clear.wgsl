@@ -0,0 +1,22 @@
+// Fills a camera's viewport with its clear color. LoadOp::Clear always clears the whole texture, which would wipe out
+// every other camera's viewport, so this is drawn instead.
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    clear_color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+// a single triangle big enough to cover the whole viewport, anything outside of it gets clipped
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 1.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return camera.clear_color;
+}
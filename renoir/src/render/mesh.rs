@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use wgpu::util::DeviceExt;
+
+use crate::render::texture::Texture;
+use crate::render::vertex::Vertex;
+
+/// Geometry submitted by the game dev, stored on the CPU side until the renderer uploads it.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    /// Drawn instead of the default texture when set. It's an Arc so that meshes sharing a material can share
+    /// the image, rather than each keeping their own copy.
+    pub texture: Option<Arc<image::DynamicImage>>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
+        Self {
+            vertices,
+            indices,
+            texture: None,
+        }
+    }
+
+    pub fn with_texture(mut self, texture: Arc<image::DynamicImage>) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+}
+
+/// An opaque reference to a mesh registered with `Meshes`, handed out by `Meshes::add`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct MeshHandle(u64);
+
+struct MeshEntry {
+    mesh: Mesh,
+    // set whenever the mesh is added or changed, so the renderer knows to (re)upload its buffers
+    dirty: bool,
+}
+
+/// All meshes the game dev has registered. This lives in RenoirAppState, and the renderer syncs its
+/// GPU buffers to it before every frame (similar to how the Camera syncs to the CameraController).
+#[derive(Default)]
+pub struct Meshes {
+    entries: HashMap<MeshHandle, MeshEntry>,
+    removed: Vec<MeshHandle>,
+    next_id: u64,
+}
+
+impl Meshes {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, mesh: Mesh) -> MeshHandle {
+        let handle = MeshHandle(self.next_id);
+        self.next_id += 1;
+        self.entries.insert(handle, MeshEntry { mesh, dirty: true });
+        handle
+    }
+
+    pub fn get(&self, handle: MeshHandle) -> Option<&Mesh> {
+        self.entries.get(&handle).map(|entry| &entry.mesh)
+    }
+
+    /// Gives mutable access to a mesh, which will be re-uploaded to the GPU on the next frame.
+    pub fn get_mut(&mut self, handle: MeshHandle) -> Option<&mut Mesh> {
+        self.entries.get_mut(&handle).map(|entry| {
+            entry.dirty = true;
+            &mut entry.mesh
+        })
+    }
+
+    /// Replaces the geometry behind `handle`, returning the old mesh, or None if the handle isn't registered.
+    pub fn update(&mut self, handle: MeshHandle, mesh: Mesh) -> Option<Mesh> {
+        self.get_mut(handle).map(|old| std::mem::replace(old, mesh))
+    }
+
+    pub fn remove(&mut self, handle: MeshHandle) -> Option<Mesh> {
+        let entry = self.entries.remove(&handle)?;
+        self.removed.push(handle);
+        Some(entry.mesh)
+    }
+
+    pub fn contains(&self, handle: MeshHandle) -> bool {
+        self.entries.contains_key(&handle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn handles(&self) -> impl Iterator<Item = MeshHandle> + '_ {
+        self.entries.keys().copied()
+    }
+}
+
+/// The GPU side of a Mesh, owned by the renderer.
+pub(crate) struct GpuMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub num_vertices: u32,
+    pub num_indices: u32,
+    pub diffuse_bind_group: Option<wgpu::BindGroup>,
+}
+
+impl GpuMesh {
+    fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_bind_group_layout: &wgpu::BindGroupLayout,
+        mesh: &Mesh,
+    ) -> Self {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Vertex Buffer"),
+            contents: bytemuck::cast_slice(&mesh.vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Index Buffer"),
+            contents: bytemuck::cast_slice(&mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let diffuse_bind_group =
+            mesh.texture.as_ref().and_then(|image| {
+                match Texture::from_image(device, queue, image, Some("Mesh Texture")) {
+                    Ok(texture) => Some(texture.create_bind_group(
+                        device,
+                        texture_bind_group_layout,
+                        Some("mesh_diffuse_bind_group"),
+                    )),
+                    Err(e) => {
+                        log::error!(
+                            "couldn't upload mesh texture, using the default one instead: {e}"
+                        );
+                        None
+                    }
+                }
+            });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            num_vertices: mesh.vertices.len() as u32,
+            num_indices: mesh.indices.len() as u32,
+            diffuse_bind_group,
+        }
+    }
+
+    /// Brings the renderer's GPU meshes up to date with the meshes in `meshes`, uploading anything new or changed
+    /// and dropping the buffers of removed meshes.
+    pub fn sync(
+        gpu_meshes: &mut BTreeMap<MeshHandle, GpuMesh>,
+        meshes: &mut Meshes,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_bind_group_layout: &wgpu::BindGroupLayout,
+    ) {
+        for handle in meshes.removed.drain(..) {
+            gpu_meshes.remove(&handle);
+        }
+
+        for (handle, entry) in meshes.entries.iter_mut().filter(|(_, entry)| entry.dirty) {
+            gpu_meshes.insert(
+                *handle,
+                GpuMesh::new(device, queue, texture_bind_group_layout, &entry.mesh),
+            );
+            entry.dirty = false;
+        }
+    }
+}
@@ -1,4 +1,8 @@
 mod instance;
+pub mod mesh;
+pub mod model;
+mod readback;
 pub mod renderer;
+pub mod scene;
 mod texture;
-mod vertex;
+pub mod vertex;
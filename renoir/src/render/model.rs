@@ -0,0 +1,255 @@
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::math::prelude::*;
+use crate::render::mesh::{Mesh, MeshHandle, Meshes};
+use crate::render::vertex::Vertex;
+
+/// Where a material's texture comes from. OBJ materials only name an image file, while glTF files usually come
+/// with their images already decoded.
+#[derive(Clone, Debug)]
+pub enum MaterialTexture {
+    Path(PathBuf),
+    Image(Arc<image::DynamicImage>),
+}
+
+/// Surface properties of a mesh, as read from a model file. The renderer doesn't draw with these yet, but they're
+/// kept around so that the game dev can look up which texture belongs to which mesh.
+#[derive(Clone, Debug)]
+pub struct Material {
+    pub name: String,
+    pub base_color: [f32; 4],
+    /// The diffuse/base color texture. Paths are resolved relative to the model's directory when the model was
+    /// loaded from a file, otherwise they're left exactly as written in the material file.
+    pub base_color_texture: Option<MaterialTexture>,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            base_color_texture: None,
+        }
+    }
+}
+
+/// One piece of a Model, each OBJ object/group (or glTF primitive) becomes one of these.
+#[derive(Clone, Debug)]
+pub struct ModelMesh {
+    pub name: String,
+    pub mesh: Mesh,
+    /// Index into the materials of the Model (or Scene) this came from
+    pub material: Option<usize>,
+}
+
+/// Geometry and materials loaded from a model file, ready to be added to `Meshes`.
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    pub meshes: Vec<ModelMesh>,
+    pub materials: Vec<Material>,
+}
+
+impl Model {
+    /// Loads an OBJ file from disk, along with any MTL files it references (looked up next to the OBJ file).
+    pub fn from_obj_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        let (models, materials) = tobj::load_obj(path, &obj_load_options())
+            .with_context(|| format!("failed to load OBJ file {}", path.display()))?;
+
+        Self::from_tobj(models, materials, Some(dir))
+    }
+
+    /// Loads an OBJ file from memory. As there's no directory to look up MTL files in, the material library (if
+    /// any) has to be passed in as well.
+    pub fn from_obj_bytes(obj: &[u8], mtl: Option<&[u8]>) -> Result<Self> {
+        let (models, materials) = tobj::load_obj_buf(
+            &mut BufReader::new(Cursor::new(obj)),
+            &obj_load_options(),
+            |_| match mtl {
+                Some(mtl) => tobj::load_mtl_buf(&mut BufReader::new(Cursor::new(mtl))),
+                None => Err(tobj::LoadError::OpenFileFailed),
+            },
+        )
+        .context("failed to parse OBJ data")?;
+
+        Self::from_tobj(models, materials, None)
+    }
+
+    fn from_tobj(
+        models: Vec<tobj::Model>,
+        materials: Result<Vec<tobj::Material>, tobj::LoadError>,
+        dir: Option<&Path>,
+    ) -> Result<Self> {
+        let materials = match materials {
+            Ok(materials) => materials,
+            // a missing MTL file shouldn't stop the geometry from loading, it just won't have materials
+            Err(tobj::LoadError::OpenFileFailed) => {
+                log::warn!(
+                    "couldn't find the MTL file referenced by an OBJ, loading without materials"
+                );
+                Vec::new()
+            }
+            Err(e) => return Err(e).context("failed to parse MTL data"),
+        };
+
+        let materials = materials
+            .into_iter()
+            .map(|material| Material {
+                name: material.name,
+                base_color: {
+                    let [r, g, b] = material.diffuse.unwrap_or([1.0, 1.0, 1.0]);
+                    [r, g, b, material.dissolve.unwrap_or(1.0)]
+                },
+                base_color_texture: material.diffuse_texture.map(|texture| {
+                    MaterialTexture::Path(match dir {
+                        Some(dir) => dir.join(texture),
+                        None => PathBuf::from(texture),
+                    })
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        let meshes = models
+            .into_iter()
+            .map(|model| {
+                let mesh = &model.mesh;
+                let vertices = (0..mesh.positions.len() / 3)
+                    .map(|i| Vertex {
+                        position: Vec3::new(
+                            mesh.positions[i * 3],
+                            mesh.positions[i * 3 + 1],
+                            mesh.positions[i * 3 + 2],
+                        ),
+                        // OBJ has V going up, while wgpu has it going down, so it's flipped here.
+                        // meshes without UVs just get (0, 0), which samples a single texel of the texture
+                        tex_coords: match mesh.texcoords.get(i * 2..i * 2 + 2) {
+                            Some(uv) => Vec2::new(uv[0], 1.0 - uv[1]),
+                            None => Vec2::zeros(),
+                        },
+                    })
+                    .collect();
+
+                // Vertex doesn't have a normal yet (there's no lighting), so normals are ignored whether or not the
+                // file has them.
+                ModelMesh {
+                    name: model.name,
+                    mesh: Mesh::new(vertices, model.mesh.indices),
+                    material: model.mesh.material_id.filter(|&id| id < materials.len()),
+                }
+            })
+            .collect();
+
+        Ok(Self { meshes, materials })
+    }
+
+    /// Registers every mesh in the model, returning the handles in the same order as `Model::meshes`.
+    pub fn add_to(&self, meshes: &mut Meshes) -> Vec<MeshHandle> {
+        self.meshes
+            .iter()
+            .map(|model_mesh| meshes.add(model_mesh.mesh.clone()))
+            .collect()
+    }
+}
+
+fn obj_load_options() -> tobj::LoadOptions {
+    tobj::LoadOptions {
+        // Vertex holds every attribute, so positions and UVs need to share one index buffer
+        single_index: true,
+        // n-gons are split into triangle fans, as the pipeline only draws triangle lists
+        triangulate: true,
+        ignore_points: true,
+        ignore_lines: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD: &str = "
+o quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 2/2 3/3 4/4
+";
+
+    #[test]
+    fn quads_are_triangulated() {
+        let model = Model::from_obj_bytes(QUAD.as_bytes(), None).unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+        let mesh = &model.meshes[0].mesh;
+        assert_eq!(model.meshes[0].name, "quad");
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices.len(), 6);
+        assert!(mesh
+            .indices
+            .iter()
+            .all(|&i| (i as usize) < mesh.vertices.len()));
+    }
+
+    #[test]
+    fn v_is_flipped() {
+        let model = Model::from_obj_bytes(QUAD.as_bytes(), None).unwrap();
+
+        for vertex in &model.meshes[0].mesh.vertices {
+            // the UVs match the positions in the file, so flipped V is 1 - y
+            assert_eq!(vertex.tex_coords.x, vertex.position.x);
+            assert_eq!(vertex.tex_coords.y, 1.0 - vertex.position.y);
+        }
+    }
+
+    #[test]
+    fn meshes_without_uvs_or_normals_still_load() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let model = Model::from_obj_bytes(obj.as_bytes(), None).unwrap();
+
+        let mesh = &model.meshes[0].mesh;
+        assert_eq!(mesh.indices, [0, 1, 2]);
+        assert_eq!(mesh.vertices[1].position, Vec3::new(1.0, 0.0, 0.0));
+        assert!(mesh.vertices.iter().all(|v| v.tex_coords == Vec2::zeros()));
+    }
+
+    #[test]
+    fn malformed_faces_are_an_error() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 nope\n";
+        assert!(Model::from_obj_bytes(obj.as_bytes(), None).is_err());
+    }
+
+    #[test]
+    fn missing_mtl_only_drops_the_materials() {
+        let obj = format!("mtllib missing.mtl\nusemtl red\n{QUAD}");
+        let model = Model::from_obj_bytes(obj.as_bytes(), None).unwrap();
+
+        assert!(model.materials.is_empty());
+        assert_eq!(model.meshes.len(), 1);
+        assert_eq!(model.meshes[0].material, None);
+    }
+
+    #[test]
+    fn materials_are_read_from_the_mtl() {
+        let obj = format!("mtllib quad.mtl\nusemtl red\n{QUAD}");
+        let mtl = "newmtl red\nKd 1 0 0\nd 0.5\nmap_Kd red.png\n";
+        let model = Model::from_obj_bytes(obj.as_bytes(), Some(mtl.as_bytes())).unwrap();
+
+        assert_eq!(model.meshes[0].material, Some(0));
+        let material = &model.materials[0];
+        assert_eq!(material.base_color, [1.0, 0.0, 0.0, 0.5]);
+        assert!(matches!(
+            &material.base_color_texture,
+            Some(MaterialTexture::Path(path)) if path == Path::new("red.png")
+        ));
+    }
+}
@@ -1,31 +1,25 @@
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
+use anyhow::{bail, ensure, Context, Result};
+
 use wgpu::util::DeviceExt;
 use wgpu::{
-    Backends, BindGroup, Buffer, Device, DeviceDescriptor, Features, InstanceDescriptor, Limits,
-    MemoryHints, PipelineCompilationOptions, PowerPreference, Queue, RenderPipeline,
-    RequestAdapterOptions, ShaderModuleDescriptor, ShaderSource, Surface, SurfaceConfiguration,
-    TextureUsages,
+    Backends, BindGroup, BindGroupLayout, Buffer, Device, DeviceDescriptor, Features,
+    InstanceDescriptor, Limits, MemoryHints, PipelineCompilationOptions, PowerPreference, Queue,
+    RenderPipeline, RequestAdapterOptions, ShaderModuleDescriptor, ShaderSource, Surface,
+    SurfaceConfiguration, TextureUsages,
 };
 
 use winit::{dpi::PhysicalSize, window::Window};
 
 use crate::math::prelude::*;
 use crate::render::instance;
+use crate::render::mesh::{GpuMesh, MeshHandle, Meshes};
+use crate::render::readback;
 use crate::render::texture;
 use crate::render::vertex;
-use crate::state::camera::{self, CameraController};
-
-#[rustfmt::skip]
-const VERTICES: &[vertex::Vertex] = &[
-    vertex::Vertex { position: Vec3::new(-0.0868241, 0.49240386, 0.0),   tex_coords: Vec2::new(0.4131759, 0.00759614) },
-    vertex::Vertex { position: Vec3::new(-0.49513406, 0.06958647, 0.0),  tex_coords: Vec2::new(0.0048659444, 0.43041354) },
-    vertex::Vertex { position: Vec3::new(-0.21918549, -0.44939706, 0.0), tex_coords: Vec2::new(0.28081453, 0.949397), },
-    vertex::Vertex { position: Vec3::new(0.35966998, -0.3473291, 0.0),   tex_coords: Vec2::new(0.85967, 0.84732914), },
-    vertex::Vertex { position: Vec3::new(0.44147372, 0.2347359, 0.0),    tex_coords: Vec2::new(0.9414737, 0.2652641), },
-];
-
-const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
+use crate::state::camera::{self, CameraController, CameraHandle, Cameras};
 
 const NUM_INSTANCES_PER_ROW: u32 = 10;
 const INSTANCE_DISPLACEMENT: Vec3 = Vec3::new(
@@ -34,26 +28,122 @@ const INSTANCE_DISPLACEMENT: Vec3 = Vec3::new(
     NUM_INSTANCES_PER_ROW as f32 * 0.5,
 );
 
+/// The texture being drawn to during a single frame.
+enum Frame<'a> {
+    Surface(wgpu::SurfaceTexture),
+    Offscreen(&'a wgpu::Texture),
+}
+
+impl Frame<'_> {
+    fn texture(&self) -> &wgpu::Texture {
+        match self {
+            Frame::Surface(output) => &output.texture,
+            Frame::Offscreen(texture) => texture,
+        }
+    }
+
+    // offscreen targets have nothing to present
+    fn present(self) {
+        if let Frame::Surface(output) = self {
+            output.present();
+        }
+    }
+}
+
+/// Where the renderer draws each frame to.
+enum RenderTarget<'a> {
+    Surface(Surface<'a>),
+    // headless renderers don't have a window to present to, so they draw into a texture that can be read back
+    Offscreen(wgpu::Texture),
+}
+
+/// The GPU side of a CameraController. Every camera has its own uniform buffer, as all of them are written before the
+/// frame is submitted.
+struct GpuCamera {
+    camera: camera::Camera,
+    uniform: camera::CameraUniform,
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl GpuCamera {
+    fn new(device: &Device, layout: &BindGroupLayout, config: &SurfaceConfiguration) -> Self {
+        let camera = camera::Camera::new(config);
+
+        let mut uniform = camera::CameraUniform::new();
+        uniform.update_view_proj(&camera);
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Camera Buffer"),
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+            label: Some("camera_bind_group"),
+        });
+
+        Self {
+            camera,
+            uniform,
+            buffer,
+            bind_group,
+        }
+    }
+
+    fn write(
+        &mut self,
+        camera_controller: &mut CameraController,
+        width: u32,
+        height: u32,
+        queue: &Queue,
+    ) {
+        self.camera.set_size(width, height);
+        self.camera.write_camera_controller_to_queue(
+            camera_controller,
+            self.uniform,
+            &self.buffer,
+            queue,
+        );
+    }
+}
+
+/// A camera to draw this frame, and where
+struct CameraView {
+    order: i32,
+    // None is the main camera
+    handle: Option<CameraHandle>,
+    rect: (u32, u32, u32, u32),
+    clear: bool,
+}
+
 pub struct Renderer<'a> {
-    surface: Surface<'a>,
+    target: RenderTarget<'a>,
     device: Device,
     queue: Queue,
     config: SurfaceConfiguration,
     pub(crate) size: PhysicalSize<u32>,
     render_pipeline: RenderPipeline,
-    vertex_buffer: Buffer,
-    index_buffer: Buffer,
-    num_indices: u32,
+    // ordered by handle so that meshes are drawn in the same order every run
+    meshes: BTreeMap<MeshHandle, GpuMesh>,
+    texture_bind_group_layout: BindGroupLayout,
     diffuse_bind_group: BindGroup,
     #[allow(dead_code)]
     diffuse_texture: texture::Texture,
     depth_texture: texture::Texture,
-    camera: camera::Camera,
-    camera_uniform: camera::CameraUniform,
-    camera_buffer: Buffer,
-    camera_bind_group: BindGroup,
+    camera_bind_group_layout: BindGroupLayout,
+    main_camera: GpuCamera,
+    cameras: HashMap<CameraHandle, GpuCamera>,
+    clear_pipeline: RenderPipeline,
     instances: Vec<instance::Instance>,
     instance_buffer: wgpu::Buffer,
+    capture_requested: bool,
+    captured_frame: Option<Result<image::RgbaImage>>,
 }
 
 impl<'a> Renderer<'a> {
@@ -74,16 +164,7 @@ impl<'a> Renderer<'a> {
         }))
         .unwrap();
 
-        let (device, queue) = pollster::block_on(adapter.request_device(
-            &DeviceDescriptor {
-                label: None,
-                required_features: Features::empty(),
-                required_limits: Limits::default(),
-                memory_hints: MemoryHints::default(),
-            },
-            None,
-        ))
-        .unwrap();
+        let (device, queue) = Self::request_device(&adapter).unwrap();
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -99,12 +180,105 @@ impl<'a> Renderer<'a> {
             format: surface_format,
             width: size.width,
             height: size.height,
-            usage: TextureUsages::RENDER_ATTACHMENT,
+            // COPY_SRC is needed to capture frames, but not every platform allows it on the surface
+            usage: TextureUsages::RENDER_ATTACHMENT
+                | (surface_caps.usages & TextureUsages::COPY_SRC),
             view_formats: vec![],
         };
 
         surface.configure(&device, &config);
 
+        Self::with_target(device, queue, config, RenderTarget::Surface(surface))
+    }
+
+    /// Creates a renderer that draws into an offscreen texture instead of a window, so it can run on machines
+    /// without a display (e.g. in tests or CI). Any adapter will do, including software and fallback adapters.
+    /// Frames can be read back with `Renderer::read_frame`.
+    pub fn new_headless(width: u32, height: u32) -> Result<Self> {
+        ensure!(
+            width > 0 && height > 0,
+            "headless renderer size must be non-zero, got {width}x{height}"
+        );
+
+        let instance = wgpu::Instance::new(&InstanceDescriptor {
+            backends: Backends::all(),
+            ..Default::default()
+        });
+
+        // prefer a real adapter, but fall back to a software one if that's all there is
+        let adapter = [false, true]
+            .into_iter()
+            .find_map(|force_fallback_adapter| {
+                pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
+                    power_preference: PowerPreference::default(),
+                    force_fallback_adapter,
+                    compatible_surface: None,
+                }))
+            })
+            .context("no graphics adapter is available, not even a fallback one")?;
+
+        let (device, queue) = Self::request_device(&adapter)?;
+
+        // not used to configure a surface, but the rest of the renderer reads its size and format from here
+        let config = SurfaceConfiguration {
+            desired_maximum_frame_latency: 2,
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width,
+            height,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: vec![],
+        };
+
+        let texture = Self::create_offscreen_texture(&device, &config);
+
+        Ok(Self::with_target(
+            device,
+            queue,
+            config,
+            RenderTarget::Offscreen(texture),
+        ))
+    }
+
+    fn request_device(adapter: &wgpu::Adapter) -> Result<(Device, Queue)> {
+        pollster::block_on(adapter.request_device(
+            &DeviceDescriptor {
+                label: None,
+                required_features: Features::empty(),
+                required_limits: Limits::default(),
+                memory_hints: MemoryHints::default(),
+            },
+            None,
+        ))
+        .context("failed to request a device from the graphics adapter")
+    }
+
+    fn create_offscreen_texture(device: &Device, config: &SurfaceConfiguration) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Target"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
+        })
+    }
+
+    fn with_target(
+        device: Device,
+        queue: Queue,
+        config: SurfaceConfiguration,
+        target: RenderTarget<'a>,
+    ) -> Self {
+        let size = PhysicalSize::new(config.width, config.height);
+
         let diffuse_bytes = include_bytes!("natalie.jpg");
         let diffuse_texture =
             texture::Texture::from_bytes(&device, &queue, diffuse_bytes, "natalie.jpg").unwrap();
@@ -134,40 +308,21 @@ impl<'a> Renderer<'a> {
                 label: Some("texture_bind_group_layout"),
             });
 
-        let diffuse_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
-                },
-            ],
-            label: Some("diffuse_bind_group"),
-        });
+        let diffuse_bind_group = diffuse_texture.create_bind_group(
+            &device,
+            &texture_bind_group_layout,
+            Some("diffuse_bind_group"),
+        );
 
         let depth_texture =
             texture::Texture::create_depth_texture(&device, &config, "depth_texture");
 
-        let camera = camera::Camera::new(&config);
-
-        let mut camera_uniform = camera::CameraUniform::new();
-        camera_uniform.update_view_proj(&camera);
-
-        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Camera Buffer"),
-            contents: bytemuck::cast_slice(&[camera_uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-
         let camera_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    // the fragment stage reads the clear color
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -178,14 +333,7 @@ impl<'a> Renderer<'a> {
                 label: Some("camera_bind_group_layout"),
             });
 
-        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-            label: Some("camera_bind_group"),
-        });
+        let main_camera = GpuCamera::new(&device, &camera_bind_group_layout, &config);
 
         let shader = device.create_shader_module(ShaderModuleDescriptor {
             label: Some("Shader"),
@@ -243,19 +391,50 @@ impl<'a> Renderer<'a> {
             cache: None,
         });
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(VERTICES),
-            usage: wgpu::BufferUsages::VERTEX,
+        let clear_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Clear Shader"),
+            source: ShaderSource::Wgsl(include_str!("clear.wgsl").into()),
         });
 
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(INDICES),
-            usage: wgpu::BufferUsages::INDEX,
-        });
+        let clear_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Clear Pipeline Layout"),
+                bind_group_layouts: &[&camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
 
-        let num_indices = INDICES.len() as u32;
+        // the depth buffer is cleared by each camera's render pass, so this only touches color
+        let clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Clear Pipeline"),
+            layout: Some(&clear_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &clear_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &clear_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: texture::Texture::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
 
         let instances = (0..NUM_INSTANCES_PER_ROW)
             .flat_map(|z| {
@@ -284,24 +463,25 @@ impl<'a> Renderer<'a> {
         });
 
         Renderer {
-            surface,
+            target,
             device,
             queue,
             config,
             size,
             render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            num_indices,
+            meshes: BTreeMap::new(),
+            texture_bind_group_layout,
             diffuse_bind_group,
             diffuse_texture,
             depth_texture,
-            camera,
-            camera_uniform,
-            camera_buffer,
-            camera_bind_group,
+            camera_bind_group_layout,
+            main_camera,
+            cameras: HashMap::new(),
+            clear_pipeline,
             instances,
             instance_buffer,
+            capture_requested: false,
+            captured_frame: None,
         }
     }
 
@@ -310,26 +490,40 @@ impl<'a> Renderer<'a> {
             self.size = new_size;
             self.config.width = new_size.width;
             self.config.height = new_size.height;
-            self.surface.configure(&self.device, &self.config);
+            match &mut self.target {
+                RenderTarget::Surface(surface) => surface.configure(&self.device, &self.config),
+                RenderTarget::Offscreen(texture) => {
+                    *texture = Self::create_offscreen_texture(&self.device, &self.config)
+                }
+            }
             self.depth_texture =
                 texture::Texture::create_depth_texture(&self.device, &self.config, "depth_texture");
         }
     }
 
+    /// Draws the scene once for every active camera, the main one and `cameras`, each into its own viewport
     pub fn render(
         &mut self,
         camera_controller: &mut CameraController,
-    ) -> Result<(), wgpu::SurfaceError> {
-        self.camera.write_camera_controller_to_queue(
-            camera_controller,
-            self.camera_uniform,
-            &self.camera_buffer,
+        cameras: &mut Cameras,
+        meshes: &mut Meshes,
+    ) -> std::result::Result<(), wgpu::SurfaceError> {
+        GpuMesh::sync(
+            &mut self.meshes,
+            meshes,
+            &self.device,
             &self.queue,
+            &self.texture_bind_group_layout,
         );
 
-        let output = self.surface.get_current_texture()?;
+        let views = self.sync_cameras(camera_controller, cameras);
+
+        let output = match &self.target {
+            RenderTarget::Surface(surface) => Frame::Surface(surface.get_current_texture()?),
+            RenderTarget::Offscreen(texture) => Frame::Offscreen(texture),
+        };
         let view = output
-            .texture
+            .texture()
             .create_view(&wgpu::TextureViewDescriptor::default());
 
         let mut encoder = self
@@ -338,22 +532,25 @@ impl<'a> Renderer<'a> {
                 label: Some("Render Encoder"),
             });
 
-        {
+        // the first pass clears the whole frame, so there's still a pass to do that when no camera is drawn
+        let passes = views.len().max(1);
+        for i in 0..passes {
+            let color_load = match i {
+                0 => wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                _ => wgpu::LoadOp::Load,
+            };
+
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
+                        load: color_load,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
+                // every camera gets a fresh depth buffer, so cameras drawn later are never hidden behind earlier ones
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                     view: &self.depth_texture.view,
                     depth_ops: Some(wgpu::Operations {
@@ -366,18 +563,266 @@ impl<'a> Renderer<'a> {
                 timestamp_writes: None,
             });
 
+            let Some(camera_view) = views.get(i) else {
+                continue;
+            };
+            let camera = match camera_view.handle {
+                Some(handle) => &self.cameras[&handle],
+                None => &self.main_camera,
+            };
+
+            let (x, y, width, height) = camera_view.rect;
+            render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+            render_pass.set_scissor_rect(x, y, width, height);
+
+            if camera_view.clear {
+                render_pass.set_pipeline(&self.clear_pipeline);
+                render_pass.set_bind_group(0, &camera.bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+            }
+
             render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
-            render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_bind_group(1, &camera.bind_group, &[]);
             render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..self.instances.len() as _);
+
+            // empty meshes are skipped, as there's nothing to draw and their buffers can't be bound
+            for mesh in self
+                .meshes
+                .values()
+                .filter(|mesh| mesh.num_vertices > 0 && mesh.num_indices > 0)
+            {
+                // meshes without a texture of their own fall back to the default diffuse texture
+                render_pass.set_bind_group(
+                    0,
+                    mesh.diffuse_bind_group
+                        .as_ref()
+                        .unwrap_or(&self.diffuse_bind_group),
+                    &[],
+                );
+                render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..mesh.num_indices, 0, 0..self.instances.len() as _);
+            }
         }
 
         self.queue.submit([encoder.finish()]);
+
+        // this has to happen before presenting, as the surface texture can't be touched afterwards
+        if std::mem::take(&mut self.capture_requested) {
+            self.captured_frame = Some(readback::read_texture(
+                &self.device,
+                &self.queue,
+                output.texture(),
+            ));
+        }
+
         output.present();
 
         Ok(())
     }
+
+    // writes every camera's uniforms and works out which ones to draw this frame, in the order they're drawn
+    fn sync_cameras(
+        &mut self,
+        camera_controller: &mut CameraController,
+        cameras: &mut Cameras,
+    ) -> Vec<CameraView> {
+        // cameras come and go, so their GPU sides are kept in sync like meshes
+        self.cameras.retain(|handle, _| cameras.contains(*handle));
+
+        let size = (self.config.width, self.config.height);
+        let mut views = Vec::new();
+
+        let controllers = std::iter::once((None, camera_controller)).chain(
+            cameras
+                .iter_mut()
+                .map(|(handle, camera)| (Some(handle), camera)),
+        );
+        for (handle, controller) in controllers {
+            if !controller.active {
+                continue;
+            }
+            let Some(rect) = controller.viewport.pixel_rect(size) else {
+                continue;
+            };
+
+            let camera = match handle {
+                Some(handle) => self.cameras.entry(handle).or_insert_with(|| {
+                    GpuCamera::new(&self.device, &self.camera_bind_group_layout, &self.config)
+                }),
+                None => &mut self.main_camera,
+            };
+            camera.write(controller, rect.2, rect.3, &self.queue);
+
+            views.push(CameraView {
+                order: controller.order,
+                handle,
+                rect,
+                clear: controller.clear_color.is_some(),
+            });
+        }
+
+        // the main camera goes first out of cameras with the same order, then the others in the order they were added
+        views.sort_by_key(|view| (view.order, view.handle));
+        views
+    }
+
+    /// Makes the renderer copy the next frame it draws, which can then be taken with `Renderer::take_captured_frame`.
+    pub fn capture_next_frame(&mut self) {
+        self.capture_requested = true;
+    }
+
+    /// The frame copied after `Renderer::capture_next_frame`, or why it couldn't be copied (e.g. the window's surface
+    /// doesn't allow it on this platform).
+    pub fn take_captured_frame(&mut self) -> Option<Result<image::RgbaImage>> {
+        self.captured_frame.take()
+    }
+
+    /// Copies the last rendered frame back from the GPU. Only headless renderers can do this, as a window's surface
+    /// texture is gone once it's been presented.
+    pub fn read_frame(&self) -> Result<image::RgbaImage> {
+        match &self.target {
+            RenderTarget::Offscreen(texture) => {
+                readback::read_texture(&self.device, &self.queue, texture)
+            }
+            RenderTarget::Surface(_) => {
+                bail!("frames can only be read back from a headless renderer")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    const SIZE: u32 = 64;
+
+    // machines without any adapter (not even a software one) can't run these, which isn't a failure
+    fn headless() -> Option<Renderer<'static>> {
+        match Renderer::new_headless(SIZE, SIZE) {
+            Ok(renderer) => Some(renderer),
+            Err(e) => {
+                eprintln!("skipping, no headless renderer: {e:#}");
+                None
+            }
+        }
+    }
+
+    fn red_square(half_size: f32) -> Mesh {
+        let vertex = |x, y| Vertex {
+            position: Vec3::new(x * half_size, y * half_size, 0.0),
+            tex_coords: Vec2::new(0.5, 0.5),
+        };
+        let red = image::RgbaImage::from_pixel(1, 1, image::Rgba([255, 0, 0, 255]));
+
+        Mesh::new(
+            vec![
+                vertex(-1.0, -1.0),
+                vertex(1.0, -1.0),
+                vertex(1.0, 1.0),
+                vertex(-1.0, 1.0),
+            ],
+            // both windings, so the square is drawn whichever way it faces
+            vec![0, 1, 2, 0, 2, 3, 0, 2, 1, 0, 3, 2],
+        )
+        .with_texture(std::sync::Arc::new(red.into()))
+    }
+
+    fn looking_down_z() -> CameraController {
+        let mut camera = CameraController::new();
+        camera.set_translate(0.0, 0.0, -10.0);
+        camera.projection = Projection::Orthographic(OrthographicSize::Height(0.5));
+        camera.clear_color = Some([0.0, 0.0, 0.0, 1.0]);
+        camera
+    }
+
+    #[test]
+    fn draws_a_mesh() {
+        let Some(mut renderer) = headless() else {
+            return;
+        };
+
+        let mut meshes = Meshes::new();
+        meshes.add(red_square(0.1));
+        renderer
+            .render(&mut looking_down_z(), &mut Cameras::new(), &mut meshes)
+            .unwrap();
+
+        let frame = renderer.read_frame().unwrap();
+        assert_eq!(frame.dimensions(), (SIZE, SIZE));
+        assert_eq!(frame.get_pixel(SIZE / 2, SIZE / 2).0, [255, 0, 0, 255]);
+        assert_eq!(frame.get_pixel(0, 0).0, [0, 0, 0, 255]);
+        assert_eq!(frame.get_pixel(SIZE - 1, SIZE - 1).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn clears_each_viewport_to_its_camera_color() {
+        let Some(mut renderer) = headless() else {
+            return;
+        };
+
+        let mut left = looking_down_z();
+        left.viewport = Viewport::grid(2, 1, 0);
+        left.clear_color = Some([0.0, 0.0, 1.0, 1.0]);
+
+        let mut right = looking_down_z();
+        right.viewport = Viewport::grid(2, 1, 1);
+        right.clear_color = Some([0.0, 1.0, 0.0, 1.0]);
+        let mut cameras = Cameras::new();
+        cameras.add(right);
+
+        renderer
+            .render(&mut left, &mut cameras, &mut Meshes::new())
+            .unwrap();
+
+        let frame = renderer.read_frame().unwrap();
+        assert_eq!(frame.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(frame.get_pixel(SIZE - 1, SIZE - 1).0, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn reading_back_without_copy_src_is_an_error() {
+        let Some(renderer) = headless() else {
+            return;
+        };
+
+        // like a window surface on a platform that doesn't allow copying from it
+        let texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        let result =
+            crate::render::readback::read_texture(&renderer.device, &renderer.queue, &texture);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn empty_meshes_are_skipped() {
+        let Some(mut renderer) = headless() else {
+            return;
+        };
+
+        let mut meshes = Meshes::new();
+        meshes.add(Mesh::default());
+        meshes.add(Mesh::new(Vec::new(), vec![0, 1, 2]));
+        renderer
+            .render(&mut looking_down_z(), &mut Cameras::new(), &mut meshes)
+            .unwrap();
+
+        let frame = renderer.read_frame().unwrap();
+        assert!(frame.pixels().all(|pixel| pixel.0 == [0, 0, 0, 255]));
+    }
 }
@@ -0,0 +1,504 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{ensure, Context, Result};
+
+#[cfg(feature = "ecs")]
+use hecs::{Entity, World};
+
+use crate::math::prelude::*;
+use crate::render::mesh::{Mesh, MeshHandle, Meshes};
+use crate::render::model::{Material, MaterialTexture, ModelMesh};
+use crate::render::vertex::Vertex;
+
+/// A node in a Scene's transform hierarchy. Indices refer to the Vecs in the Scene this node belongs to.
+#[derive(Clone, Debug)]
+pub struct SceneNode {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// Relative to the parent node
+    pub transform: Mat4,
+    /// The transform with every parent's transform applied, i.e. relative to the scene's origin
+    pub world_transform: Mat4,
+    /// Indices into `Scene::meshes`, a glTF mesh can be made up of several primitives so there can be more than one
+    pub meshes: Vec<usize>,
+    /// Index into `Scene::cameras`
+    pub camera: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum SceneProjection {
+    /// `fovy` is in radians. A missing aspect ratio means the window's should be used, and a missing `zfar` means
+    /// the projection is infinite.
+    Perspective {
+        aspect: Option<f32>,
+        fovy: f32,
+        znear: f32,
+        zfar: Option<f32>,
+    },
+    Orthographic {
+        xmag: f32,
+        ymag: f32,
+        znear: f32,
+        zfar: f32,
+    },
+}
+
+/// A camera defined in a scene file. Like in glTF, it looks down the -Z axis of the node it's attached to.
+#[derive(Clone, Debug)]
+pub struct SceneCamera {
+    pub name: String,
+    pub projection: SceneProjection,
+}
+
+/// Everything imported from a glTF file. This is just plain data, use `Scene::add_to` to draw it, or
+/// `Scene::spawn_into` to also get an entity per node when the "ecs" feature is enabled.
+#[derive(Clone, Debug, Default)]
+pub struct Scene {
+    pub nodes: Vec<SceneNode>,
+    /// The top level nodes of the scene, everything else is reachable through their children
+    pub roots: Vec<usize>,
+    pub meshes: Vec<ModelMesh>,
+    pub materials: Vec<Material>,
+    pub cameras: Vec<SceneCamera>,
+}
+
+impl Scene {
+    /// Imports a .gltf or .glb file from disk, along with any buffers and images it references.
+    pub fn from_gltf_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let (document, buffers, images) = gltf::import(path)
+            .with_context(|| format!("failed to import glTF file {}", path.display()))?;
+
+        Self::from_gltf(document, buffers, images)
+    }
+
+    /// Imports a .gltf or .glb file from memory. Only embedded (.glb or data URI) buffers and images can be loaded
+    /// this way, as there's no directory to look up other files in.
+    pub fn from_gltf_bytes(bytes: &[u8]) -> Result<Self> {
+        let (document, buffers, images) =
+            gltf::import_slice(bytes).context("failed to import glTF data")?;
+
+        Self::from_gltf(document, buffers, images)
+    }
+
+    fn from_gltf(
+        document: gltf::Document,
+        buffers: Vec<gltf::buffer::Data>,
+        images: Vec<gltf::image::Data>,
+    ) -> Result<Self> {
+        let images = images
+            .into_iter()
+            .enumerate()
+            .map(|(i, data)| {
+                gltf_image_to_dynamic(data).map(Arc::new).with_context(|| {
+                    format!("image {i} of the glTF file has an unsupported format")
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let materials = document
+            .materials()
+            .map(|material| {
+                let pbr = material.pbr_metallic_roughness();
+                Material {
+                    name: material.name().unwrap_or_default().to_owned(),
+                    base_color: pbr.base_color_factor(),
+                    base_color_texture: pbr.base_color_texture().map(|info| {
+                        MaterialTexture::Image(Arc::clone(&images[info.texture().source().index()]))
+                    }),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut meshes = Vec::new();
+        // glTF mesh index -> indices into `meshes`, one for each primitive that could be loaded
+        let mut primitives_of_mesh = Vec::new();
+
+        for mesh in document.meshes() {
+            let mut primitives = Vec::new();
+
+            for (i, primitive) in mesh.primitives().enumerate() {
+                let name = match mesh.name() {
+                    Some(name) => format!("{name}.{i}"),
+                    None => format!("mesh{}.{i}", mesh.index()),
+                };
+
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    log::warn!(
+                        "skipping glTF primitive {name}, only triangle lists are supported (found {:?})",
+                        primitive.mode()
+                    );
+                    continue;
+                }
+
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+                let Some(positions) = reader.read_positions() else {
+                    log::warn!("skipping glTF primitive {name}, as it has no positions");
+                    continue;
+                };
+
+                let mut vertices = positions
+                    .map(|[x, y, z]| Vertex {
+                        position: Vec3::new(x, y, z),
+                        tex_coords: Vec2::zeros(),
+                    })
+                    .collect::<Vec<_>>();
+
+                // glTF UVs already have V going down like wgpu, so unlike OBJ they aren't flipped
+                if let Some(tex_coords) = reader.read_tex_coords(0) {
+                    vertices
+                        .iter_mut()
+                        .zip(tex_coords.into_f32())
+                        .for_each(|(vertex, [u, v])| vertex.tex_coords = Vec2::new(u, v));
+                }
+
+                // non-indexed primitives just draw their vertices in order
+                let indices = match reader.read_indices() {
+                    Some(indices) => indices.into_u32().collect(),
+                    None => (0..vertices.len() as u32).collect(),
+                };
+
+                let material = primitive.material().index();
+                let mut mesh = Mesh::new(vertices, indices);
+                if let Some(MaterialTexture::Image(image)) =
+                    material.and_then(|i| materials[i].base_color_texture.as_ref())
+                {
+                    mesh = mesh.with_texture(Arc::clone(image));
+                }
+
+                primitives.push(meshes.len());
+                meshes.push(ModelMesh {
+                    name,
+                    mesh,
+                    material,
+                });
+            }
+
+            primitives_of_mesh.push(primitives);
+        }
+
+        let cameras = document
+            .cameras()
+            .map(|camera| SceneCamera {
+                name: camera.name().unwrap_or_default().to_owned(),
+                projection: match camera.projection() {
+                    gltf::camera::Projection::Perspective(p) => SceneProjection::Perspective {
+                        aspect: p.aspect_ratio(),
+                        fovy: p.yfov(),
+                        znear: p.znear(),
+                        zfar: p.zfar(),
+                    },
+                    gltf::camera::Projection::Orthographic(o) => SceneProjection::Orthographic {
+                        xmag: o.xmag(),
+                        ymag: o.ymag(),
+                        znear: o.znear(),
+                        zfar: o.zfar(),
+                    },
+                },
+            })
+            .collect();
+
+        let mut nodes = document
+            .nodes()
+            .map(|node| SceneNode {
+                name: node.name().unwrap_or_default().to_owned(),
+                parent: None,
+                children: node.children().map(|child| child.index()).collect(),
+                transform: Mat4::from(node.transform().matrix()),
+                world_transform: Mat4::identity(),
+                meshes: node
+                    .mesh()
+                    .map(|mesh| primitives_of_mesh[mesh.index()].clone())
+                    .unwrap_or_default(),
+                camera: node.camera().map(|camera| camera.index()),
+            })
+            .collect::<Vec<_>>();
+
+        for i in 0..nodes.len() {
+            for child in nodes[i].children.clone() {
+                nodes[child].parent = Some(i);
+            }
+        }
+
+        // files can hold several scenes, but only the default one (or the first if none is marked) is imported.
+        // files without any scenes just use every node that doesn't have a parent
+        let roots = match document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+        {
+            Some(scene) => scene.nodes().map(|node| node.index()).collect(),
+            None => (0..nodes.len())
+                .filter(|&i| nodes[i].parent.is_none())
+                .collect::<Vec<_>>(),
+        };
+
+        // nodes have to form a tree, a node reached twice either has two parents or is its own ancestor (which
+        // would otherwise loop forever)
+        let mut visited = vec![false; nodes.len()];
+        let mut stack = roots
+            .iter()
+            .map(|&root| (root, Mat4::identity()))
+            .collect::<Vec<_>>();
+        while let Some((i, parent_transform)) = stack.pop() {
+            ensure!(
+                !visited[i],
+                "node {i} is reached more than once, the node hierarchy isn't a tree"
+            );
+            visited[i] = true;
+
+            let world_transform = parent_transform * nodes[i].transform;
+            nodes[i].world_transform = world_transform;
+            stack.extend(
+                nodes[i]
+                    .children
+                    .iter()
+                    .map(|&child| (child, world_transform)),
+            );
+        }
+
+        Ok(Self {
+            nodes,
+            roots,
+            meshes,
+            materials,
+            cameras,
+        })
+    }
+
+    /// Calls `f` on every node reachable from the roots, parents always come before their children. Every node is
+    /// visited at most once, even if the nodes have been changed so that they no longer form a tree.
+    fn visit_nodes(&self, mut f: impl FnMut(usize, &SceneNode)) {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(i) = stack.pop() {
+            match visited.get(i) {
+                Some(false) => visited[i] = true,
+                Some(true) => {
+                    log::warn!(
+                        "scene node {i} is reached more than once, only visiting it the first time"
+                    );
+                    continue;
+                }
+                None => {
+                    log::warn!(
+                        "skipping scene node {i}, the scene only has {} nodes",
+                        self.nodes.len()
+                    );
+                    continue;
+                }
+            }
+
+            f(i, &self.nodes[i]);
+            stack.extend(self.nodes[i].children.iter().rev());
+        }
+    }
+
+    /// Registers a copy of every mesh in the scene, with its node's world transform baked into the vertices.
+    /// Returns the handles added for each node, indexed the same way as `Scene::nodes`.
+    pub fn add_to(&self, meshes: &mut Meshes) -> Vec<Vec<MeshHandle>> {
+        let mut handles = vec![Vec::new(); self.nodes.len()];
+
+        self.visit_nodes(|i, node| {
+            handles[i] = node
+                .meshes
+                .iter()
+                .map(|&mesh| {
+                    let mut mesh = self.meshes[mesh].mesh.clone();
+                    mesh.vertices.iter_mut().for_each(|vertex| {
+                        vertex.position = node
+                            .world_transform
+                            .transform_point(&Point3::from(vertex.position))
+                            .coords;
+                    });
+                    meshes.add(mesh)
+                })
+                .collect();
+        });
+
+        handles
+    }
+
+    /// Adds the scene's meshes (see `Scene::add_to`), then spawns an entity for every node. Each entity gets a
+    /// `NodeName` and `NodeTransform`, plus `Parent`, `Children`, `NodeMeshes` and `SceneCamera` components when
+    /// the node has them. The spawned entities are returned, indexed the same way as `Scene::nodes`.
+    #[cfg(feature = "ecs")]
+    pub fn spawn_into(&self, world: &mut World, meshes: &mut Meshes) -> Vec<Option<Entity>> {
+        let handles = self.add_to(meshes);
+        let mut entities = vec![None; self.nodes.len()];
+
+        self.visit_nodes(|i, node| {
+            let entity = world.spawn((
+                NodeName(node.name.clone()),
+                NodeTransform {
+                    local: node.transform,
+                    world: node.world_transform,
+                },
+            ));
+
+            // parents are visited first, so their entity already exists
+            if let Some(parent) = node.parent.and_then(|parent| entities[parent]) {
+                let _ = world.insert_one(entity, Parent(parent));
+            }
+            if !handles[i].is_empty() {
+                let _ = world.insert_one(entity, NodeMeshes(handles[i].clone()));
+            }
+            if let Some(camera) = node.camera {
+                let _ = world.insert_one(entity, self.cameras[camera].clone());
+            }
+
+            entities[i] = Some(entity);
+        });
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let Some(entity) = entities[i] else { continue };
+            let children = node
+                .children
+                .iter()
+                .filter_map(|&child| entities[child])
+                .collect::<Vec<_>>();
+            if !children.is_empty() {
+                let _ = world.insert_one(entity, Children(children));
+            }
+        }
+
+        entities
+    }
+}
+
+/// ECS component holding the name of the scene node an entity was spawned from.
+#[cfg(feature = "ecs")]
+#[derive(Clone, Debug)]
+pub struct NodeName(pub String);
+
+/// ECS component holding a scene node's transforms, see `SceneNode`.
+#[cfg(feature = "ecs")]
+#[derive(Clone, Copy, Debug)]
+pub struct NodeTransform {
+    pub local: Mat4,
+    pub world: Mat4,
+}
+
+#[cfg(feature = "ecs")]
+#[derive(Clone, Copy, Debug)]
+pub struct Parent(pub Entity);
+
+#[cfg(feature = "ecs")]
+#[derive(Clone, Debug)]
+pub struct Children(pub Vec<Entity>);
+
+/// ECS component holding the meshes that were added for a scene node.
+#[cfg(feature = "ecs")]
+#[derive(Clone, Debug)]
+pub struct NodeMeshes(pub Vec<MeshHandle>);
+
+fn gltf_image_to_dynamic(data: gltf::image::Data) -> Option<image::DynamicImage> {
+    use gltf::image::Format;
+    use image::{DynamicImage, ImageBuffer};
+
+    let (width, height) = (data.width, data.height);
+    // the gltf crate stores 16 and 32 bit channels as native endian bytes
+    let u16s = |pixels: &[u8]| bytemuck::pod_collect_to_vec::<u8, u16>(pixels);
+    let f32s = |pixels: &[u8]| bytemuck::pod_collect_to_vec::<u8, f32>(pixels);
+
+    match data.format {
+        Format::R8 => {
+            ImageBuffer::from_raw(width, height, data.pixels).map(DynamicImage::ImageLuma8)
+        }
+        Format::R8G8 => {
+            ImageBuffer::from_raw(width, height, data.pixels).map(DynamicImage::ImageLumaA8)
+        }
+        Format::R8G8B8 => {
+            ImageBuffer::from_raw(width, height, data.pixels).map(DynamicImage::ImageRgb8)
+        }
+        Format::R8G8B8A8 => {
+            ImageBuffer::from_raw(width, height, data.pixels).map(DynamicImage::ImageRgba8)
+        }
+        Format::R16 => {
+            ImageBuffer::from_raw(width, height, u16s(&data.pixels)).map(DynamicImage::ImageLuma16)
+        }
+        Format::R16G16 => {
+            ImageBuffer::from_raw(width, height, u16s(&data.pixels)).map(DynamicImage::ImageLumaA16)
+        }
+        Format::R16G16B16 => {
+            ImageBuffer::from_raw(width, height, u16s(&data.pixels)).map(DynamicImage::ImageRgb16)
+        }
+        Format::R16G16B16A16 => {
+            ImageBuffer::from_raw(width, height, u16s(&data.pixels)).map(DynamicImage::ImageRgba16)
+        }
+        Format::R32G32B32FLOAT => {
+            ImageBuffer::from_raw(width, height, f32s(&data.pixels)).map(DynamicImage::ImageRgb32F)
+        }
+        Format::R32G32B32A32FLOAT => {
+            ImageBuffer::from_raw(width, height, f32s(&data.pixels)).map(DynamicImage::ImageRgba32F)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gltf_with_nodes(nodes: &str, scene: &str) -> String {
+        format!(
+            r#"{{"asset": {{"version": "2.0"}}, "nodes": {nodes}, "scenes": [{{"nodes": {scene}}}], "scene": 0}}"#
+        )
+    }
+
+    #[test]
+    fn world_transforms_follow_the_hierarchy() {
+        let gltf = gltf_with_nodes(
+            r#"[{"children": [1], "translation": [1, 0, 0]}, {"translation": [0, 2, 0]}]"#,
+            "[0]",
+        );
+        let scene = Scene::from_gltf_bytes(gltf.as_bytes()).unwrap();
+
+        assert_eq!(scene.nodes[1].parent, Some(0));
+        let origin = scene.nodes[1]
+            .world_transform
+            .transform_point(&Point3::origin());
+        assert_eq!(origin, Point3::new(1.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn node_that_is_its_own_ancestor_is_an_error() {
+        let gltf = gltf_with_nodes(r#"[{"children": [1]}, {"children": [0]}]"#, "[0]");
+        assert!(Scene::from_gltf_bytes(gltf.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn hand_built_cycles_are_only_visited_once() {
+        let node = |children: Vec<usize>| SceneNode {
+            name: String::new(),
+            parent: None,
+            children,
+            transform: Mat4::identity(),
+            world_transform: Mat4::identity(),
+            meshes: vec![0],
+            camera: None,
+        };
+        let scene = Scene {
+            nodes: vec![node(vec![1]), node(vec![0, 5])],
+            roots: vec![0],
+            meshes: vec![ModelMesh {
+                name: String::new(),
+                mesh: Mesh::default(),
+                material: None,
+            }],
+            ..Default::default()
+        };
+
+        let mut meshes = Meshes::new();
+        let handles = scene.add_to(&mut meshes);
+        assert_eq!(handles.iter().map(Vec::len).collect::<Vec<_>>(), [1, 1]);
+        assert_eq!(meshes.len(), 2);
+    }
+
+    #[test]
+    fn node_with_two_parents_is_an_error() {
+        let gltf = gltf_with_nodes(r#"[{"children": [2]}, {"children": [2]}, {}]"#, "[0, 1]");
+        assert!(Scene::from_gltf_bytes(gltf.as_bytes()).is_err());
+    }
+}
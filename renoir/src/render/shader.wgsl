@@ -9,6 +9,7 @@ struct InstanceInput {
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
+    clear_color: vec4<f32>,
 };
 
 @group(1) @binding(0)
@@ -27,4 +27,4 @@ impl Vertex {
             ],
         }
     }
-}
\ No newline at end of file
+}
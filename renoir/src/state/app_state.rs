@@ -1,7 +1,13 @@
-use crate::state::camera::CameraController;
+use std::path::PathBuf;
+
+use crate::render::mesh::Meshes;
+use crate::state::camera::{CameraController, Cameras};
+use crate::state::capture::FrameCapture;
 use crate::state::flow::Flow;
+use crate::state::frame_stats::FrameStats;
 use crate::state::input::RenoirInput;
 use crate::state::time::DeltaTime;
+use crate::state::window_info::WindowInfo;
 use crate::state::window_options::WindowOptions;
 
 #[cfg(feature = "ecs")]
@@ -10,10 +16,17 @@ use hecs::World;
 /// RenoirAppState contains everything that the game dev can modify and read while the app is running.
 #[derive(Default)]
 pub struct RenoirAppState {
+    /// The main camera
     pub camera: CameraController,
+    /// Any other cameras, e.g. for split-screen
+    pub cameras: Cameras,
+    pub capture: FrameCapture,
     pub input: RenoirInput,
+    pub meshes: Meshes,
     pub flow: Flow,
     pub time: DeltaTime,
+    pub stats: FrameStats,
+    pub window: WindowInfo,
     pub window_options: WindowOptions,
     #[cfg(feature = "ecs")]
     pub world: World,
@@ -23,9 +36,14 @@ impl RenoirAppState {
     pub fn new() -> Self {
         Self {
             camera: CameraController::new(),
+            cameras: Cameras::new(),
+            capture: FrameCapture::new(),
             input: RenoirInput::new(),
+            meshes: Meshes::new(),
             flow: Flow::new(),
             time: DeltaTime::new(),
+            stats: FrameStats::new(),
+            window: WindowInfo::new(),
             window_options: WindowOptions::new(),
             #[cfg(feature = "ecs")]
             world: World::new(),
@@ -35,4 +53,9 @@ impl RenoirAppState {
     pub fn close(&mut self) {
         self.flow.should_close = true;
     }
+
+    /// Saves the next rendered frame to `path` as a PNG, see FrameCapture for recording every frame instead.
+    pub fn capture_screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.capture.screenshot(path);
+    }
 }
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use super::CameraController;
+
+/// An opaque reference to a camera added to `Cameras`, handed out by `Cameras::add`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct CameraHandle(u64);
+
+/// Cameras drawn as well as the main one (`RenoirAppState::camera`). Each camera draws the whole scene into its own
+/// viewport, which is all split-screen or a minimap takes:
+/// ```
+/// # use renoir::prelude::*;
+/// let mut ren = RenoirAppState::new();
+/// ren.camera.viewport = Viewport::grid(2, 1, 0);
+///
+/// let mut player_two = CameraController::new();
+/// player_two.viewport = Viewport::grid(2, 1, 1);
+/// let player_two = ren.cameras.add(player_two);
+///
+/// // later on, e.g. in the main function
+/// if let Some(camera) = ren.cameras.get_mut(player_two) {
+///     camera.set_translate(5.0, 1.0, -2.0);
+/// }
+/// ```
+#[derive(Default)]
+pub struct Cameras {
+    entries: HashMap<CameraHandle, CameraController>,
+    next_id: u64,
+}
+
+impl Cameras {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, camera: CameraController) -> CameraHandle {
+        let handle = CameraHandle(self.next_id);
+        self.next_id += 1;
+        self.entries.insert(handle, camera);
+        handle
+    }
+
+    pub fn get(&self, handle: CameraHandle) -> Option<&CameraController> {
+        self.entries.get(&handle)
+    }
+
+    pub fn get_mut(&mut self, handle: CameraHandle) -> Option<&mut CameraController> {
+        self.entries.get_mut(&handle)
+    }
+
+    pub fn remove(&mut self, handle: CameraHandle) -> Option<CameraController> {
+        self.entries.remove(&handle)
+    }
+
+    pub fn contains(&self, handle: CameraHandle) -> bool {
+        self.entries.contains_key(&handle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn handles(&self) -> impl Iterator<Item = CameraHandle> + '_ {
+        self.entries.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (CameraHandle, &CameraController)> {
+        self.entries
+            .iter()
+            .map(|(handle, camera)| (*handle, camera))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (CameraHandle, &mut CameraController)> {
+        self.entries
+            .iter_mut()
+            .map(|(handle, camera)| (*handle, camera))
+    }
+}
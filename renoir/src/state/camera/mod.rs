@@ -0,0 +1,329 @@
+mod cameras;
+mod modes;
+mod screen;
+mod viewport;
+
+pub use cameras::{CameraHandle, Cameras};
+pub use modes::{CameraMode, FlyCamera, FollowCamera, OrbitCamera};
+pub use screen::Ray;
+pub use viewport::Viewport;
+
+use wgpu::SurfaceConfiguration;
+
+use crate::math::prelude::*;
+use crate::math::OPENGL_TO_WGPU_MATRIX;
+
+pub enum TransformSpace {
+    Local,
+    World,
+}
+
+/// How the camera flattens the world onto the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Projection {
+    /// Things get smaller the further away they are, like in real life
+    #[default]
+    Perspective,
+    /// Things stay the same size no matter how far away they are, for 2D games, isometric views and editors
+    Orthographic(OrthographicSize),
+}
+
+/// How much of the world an orthographic camera shows. Either way the width follows from the viewport's aspect ratio.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrthographicSize {
+    /// The height of the view in world units, no matter how big the window is
+    Height(f32),
+    /// How many pixels one world unit takes up, so a bigger viewport shows more of the world (good for pixel art)
+    PixelsPerUnit(f32),
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)] // sidenote: its pretty cool that nalgebra types are compatible with bytemuck with a feature flag
+pub struct CameraUniform {
+    view_proj: Mat4,
+    // only read when the camera clears its viewport
+    clear_color: [f32; 4],
+}
+
+impl CameraUniform {
+    pub fn new() -> Self {
+        Self {
+            view_proj: Mat4::identity(),
+            clear_color: [0.0; 4],
+        }
+    }
+
+    pub fn update_view_proj(&mut self, camera: &Camera) {
+        self.view_proj = camera.build_view_projection_matrix();
+    }
+
+    pub fn update_clear_color(&mut self, camera: &Camera) {
+        self.clear_color = camera.clear_color;
+    }
+}
+
+pub struct Camera {
+    pub pos: Point3,
+    pub target: Point3,
+    up: Vec3,
+    projection: Projection,
+    // the size of the camera's viewport in pixels
+    width: f32,
+    height: f32,
+    fovy: f32,
+    znear: f32,
+    zfar: f32,
+    clear_color: [f32; 4],
+}
+
+impl Camera {
+    pub fn new(config: &SurfaceConfiguration) -> Self {
+        Self {
+            pos: Point3::new(0.0, 0.0, 10.0),
+            target: Point3::new(0.0, 0.0, 0.0),
+            up: Vec3::y(),
+            projection: Projection::Perspective,
+            width: config.width as f32,
+            height: config.height as f32,
+            fovy: 90f32.to_radians(),
+            znear: 0.1,
+            zfar: 100.0,
+            clear_color: [0.0; 4],
+        }
+    }
+
+    pub(crate) fn set_size(&mut self, width: u32, height: u32) {
+        self.width = width as f32;
+        self.height = height as f32;
+    }
+
+    fn build_view_projection_matrix(&self) -> Mat4 {
+        let view = Mat4::look_at_rh(&self.pos, &self.target, &self.up);
+        let proj = projection_matrix(
+            self.projection,
+            self.fovy,
+            self.znear,
+            self.zfar,
+            self.width,
+            self.height,
+        );
+        OPENGL_TO_WGPU_MATRIX * proj * view
+    }
+
+    pub(crate) fn sync_to(&mut self, camera_controller: &CameraController) {
+        self.pos = camera_controller.pos;
+        self.target = camera_controller.target;
+        self.projection = camera_controller.projection;
+        self.up = camera_controller.up;
+        self.fovy = camera_controller.fovy;
+        self.znear = camera_controller.znear;
+        self.zfar = camera_controller.zfar;
+        self.clear_color = camera_controller.clear_color.unwrap_or_default();
+    }
+
+    pub(crate) fn write_camera_controller_to_queue(
+        &mut self,
+        camera_controller: &mut CameraController,
+        mut camera_uniform: CameraUniform,
+        camera_buffer: &wgpu::Buffer,
+        queue: &wgpu::Queue,
+    ) {
+        camera_controller.update();
+        self.sync_to(camera_controller);
+        camera_uniform.update_view_proj(self);
+        camera_uniform.update_clear_color(self);
+        queue.write_buffer(camera_buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+    }
+}
+
+// shared by Camera and CameraController, so that what the game works out about the view always matches what's drawn
+fn projection_matrix(
+    projection: Projection,
+    fovy: f32,
+    znear: f32,
+    zfar: f32,
+    width: f32,
+    height: f32,
+) -> Mat4 {
+    let aspect = width / height;
+
+    match projection {
+        Projection::Perspective => Mat4::new_perspective(aspect, fovy, znear, zfar),
+        Projection::Orthographic(size) => {
+            let view_height = match size {
+                OrthographicSize::Height(view_height) => view_height,
+                OrthographicSize::PixelsPerUnit(pixels) => height / pixels,
+            };
+            let (half_width, half_height) = (view_height * aspect * 0.5, view_height * 0.5);
+
+            Mat4::new_orthographic(
+                -half_width,
+                half_width,
+                -half_height,
+                half_height,
+                znear,
+                zfar,
+            )
+        }
+    }
+}
+
+pub struct CameraController {
+    pub pos: Point3,
+    pub target: Point3,
+    /// Which way is up on screen, usually +Y
+    pub up: Vec3,
+    pub projection: Projection,
+    /// Inactive cameras aren't drawn
+    pub active: bool,
+    /// The part of the window this camera draws to, the whole window by default
+    pub viewport: Viewport,
+    /// Cameras are drawn from the lowest order up, so higher orders end up on top (e.g. a minimap over the game)
+    pub order: i32,
+    /// What the viewport is filled with before the camera draws, as linear RGBA. None draws over whatever cameras
+    /// with a lower order drew there, and anywhere no camera clears is black.
+    pub clear_color: Option<[f32; 4]>,
+    // vertical, in radians like nalgebra wants
+    fovy: f32,
+    znear: f32,
+    zfar: f32,
+    mode: CameraMode,
+    iso: Mat4,
+    rot: UnitQuat,
+}
+
+impl CameraController {
+    pub fn new() -> Self {
+        Self {
+            pos: Point3::new(0.0, 1.0, -2.0),
+            target: Point3::new(0.0, 0.0, 0.0),
+            up: Vec3::y(),
+            projection: Projection::Perspective,
+            active: true,
+            viewport: Viewport::FULL,
+            order: 0,
+            clear_color: Some([0.1, 0.2, 0.3, 1.0]),
+            fovy: 90f32.to_radians(),
+            znear: 0.1,
+            zfar: 100.0,
+            mode: CameraMode::Manual,
+            iso: Mat4::identity(),
+            rot: UnitQuat::identity(),
+        }
+    }
+
+    /// The vertical field of view of a perspective camera, in degrees (90 by default)
+    pub fn fov_degrees(&self) -> f32 {
+        self.fovy.to_degrees()
+    }
+
+    pub fn set_fov_degrees(&mut self, fov: f32) {
+        self.set_fov_radians(fov.to_radians());
+    }
+
+    /// The vertical field of view of a perspective camera, in radians
+    pub fn fov_radians(&self) -> f32 {
+        self.fovy
+    }
+
+    /// Anything outside of 0 to 180 degrees can't be drawn, so the FOV is kept just inside that range
+    pub fn set_fov_radians(&mut self, fov: f32) {
+        self.fovy = fov.clamp(0.01, std::f32::consts::PI - 0.01);
+    }
+
+    /// How close something can get to the camera before it's cut off (0.1 by default)
+    pub fn near(&self) -> f32 {
+        self.znear
+    }
+
+    /// How far away something can be before it's cut off (100.0 by default)
+    pub fn far(&self) -> f32 {
+        self.zfar
+    }
+
+    /// Sets the near and far clip planes. A smaller range between them means fewer depth artifacts, so it's worth
+    /// keeping the far plane as close as the scene allows.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        if near <= 0.0 || far <= near {
+            log::warn!("ignoring invalid camera clip planes (near {near}, far {far})");
+            return;
+        }
+        self.znear = near;
+        self.zfar = far;
+    }
+
+    // TODO: move this into rotate_around_axis prob, and get rid of the 'iso' field.
+    fn rebuild_iso(&mut self) {
+        self.iso = (Trans3::new(self.pos.x, self.pos.y, self.pos.z)
+            * Rot3::from(self.rot).transpose())
+        .to_matrix();
+    }
+
+    pub fn update(&mut self) {
+        self.target = self.look_target();
+    }
+
+    // where the renderer will have the camera looking once it calls update
+    fn look_target(&self) -> Point3 {
+        self.pos + (Rot3::from(self.rot).transpose() * Vec3::z())
+    }
+
+    /// The matrix that takes world space to clip space for a view of the given size in pixels, the same one the
+    /// renderer draws with
+    pub fn view_projection_matrix(&self, width: f32, height: f32) -> Mat4 {
+        let view = Mat4::look_at_rh(&self.pos, &self.look_target(), &self.up);
+        let proj = projection_matrix(
+            self.projection,
+            self.fovy,
+            self.znear,
+            self.zfar,
+            width,
+            height,
+        );
+        OPENGL_TO_WGPU_MATRIX * proj * view
+    }
+
+    pub fn rotate_x(&mut self, angle: f32) {
+        self.rotate_around_axis(Vec3::x().xyz(), angle, TransformSpace::Local);
+    }
+
+    pub fn rotate_y(&mut self, angle: f32) {
+        self.rotate_around_axis(Vec3::y().xyz(), angle, TransformSpace::World);
+    }
+
+    pub fn rotate_around_axis(&mut self, axis: Vec3, angle: f32, space: TransformSpace) {
+        let axis = axis.normalize();
+        let axis = match space {
+            TransformSpace::Local => axis,
+            TransformSpace::World => self.iso.try_inverse().unwrap().transform_vector(&axis),
+        };
+        self.rot = UnitQuat::from_scaled_axis(axis * angle) * self.rot;
+        self.rebuild_iso();
+        self.update();
+    }
+
+    pub fn set_translate(&mut self, x: f32, y: f32, z: f32) {
+        self.pos.x = x;
+        self.pos.y = y;
+        self.pos.z = z;
+        self.rebuild_iso();
+    }
+
+    // points the camera at target from pos, keeping the rotation in sync so that switching back to manual works
+    fn look_from(&mut self, pos: Point3, target: Point3) {
+        self.pos = pos;
+
+        let direction = target - pos;
+        if direction.norm() > f32::EPSILON {
+            self.rot = UnitQuat::face_towards(&direction, &self.up).inverse();
+        }
+        self.rebuild_iso();
+        self.update();
+    }
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,373 @@
+use crate::math::prelude::*;
+use crate::state::input::{AxisBinding, Key, MouseBtn, RenoirInput};
+
+use super::CameraController;
+
+/// What moves the camera each frame. Switch between modes at any time with `CameraController::set_mode`:
+/// ```
+/// # use renoir::prelude::*;
+/// let mut camera = CameraController::new();
+/// camera.set_mode(CameraMode::Follow(FollowCamera::new(Vec3::new(0.0, 3.0, -6.0))));
+///
+/// // then every frame, e.g. in the main function
+/// if let CameraMode::Follow(follow) = camera.mode_mut() {
+///     follow.target = Point3::new(1.0, 0.0, 0.0);
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum CameraMode {
+    /// Only moved by the game, with `rotate_x`, `rotate_y` and `set_translate`
+    #[default]
+    Manual,
+    Orbit(OrbitCamera),
+    Fly(FlyCamera),
+    Follow(FollowCamera),
+}
+
+/// Circles around a point, for editors, model viewers and strategy games. Dragging with the mouse rotates around the
+/// target, and scrolling zooms in and out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrbitCamera {
+    pub target: Point3,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// The angle around the target's Y axis, in radians
+    pub yaw: f32,
+    /// The angle above the target, in radians
+    pub pitch: f32,
+    /// How far pitch can go up or down, in radians (89 degrees by default, going past 90 flips the camera over)
+    pub pitch_limit: f32,
+    /// Radians per unit of mouse movement
+    pub sensitivity: f32,
+    /// The fraction of the distance each line of scrolling zooms by
+    pub zoom_speed: f32,
+    /// The mouse button that has to be held to rotate, None to always rotate with the mouse
+    pub rotate_button: Option<MouseBtn>,
+}
+
+impl OrbitCamera {
+    pub fn new(target: Point3, distance: f32) -> Self {
+        Self {
+            target,
+            distance,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            target: Point3::origin(),
+            distance: 5.0,
+            min_distance: 0.5,
+            max_distance: 100.0,
+            yaw: 0.0,
+            pitch: 30f32.to_radians(),
+            pitch_limit: 89f32.to_radians(),
+            sensitivity: 0.005,
+            zoom_speed: 0.1,
+            rotate_button: Some(MouseBtn::Left),
+        }
+    }
+}
+
+/// A free flying first person camera, moved with the keyboard and turned with the mouse. This works best with the
+/// cursor grabbed (see `WindowOptions`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlyCamera {
+    /// Units per second
+    pub speed: f32,
+    /// Radians per unit of mouse movement
+    pub sensitivity: f32,
+    /// How far the camera can look up or down, in radians (89 degrees by default)
+    pub pitch_limit: f32,
+    /// Left and right, relative to where the camera is looking (A/D by default)
+    pub strafe: AxisBinding,
+    /// Straight up and down (Shift/Space by default)
+    pub lift: AxisBinding,
+    /// Forwards and backwards, relative to where the camera is looking (S/W by default)
+    pub forward: AxisBinding,
+    /// The mouse button that has to be held to look around, None to always look with the mouse
+    pub look_button: Option<MouseBtn>,
+    // picked up from wherever the camera was looking when the mode was set
+    yaw: f32,
+    pitch: f32,
+}
+
+impl FlyCamera {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for FlyCamera {
+    fn default() -> Self {
+        Self {
+            speed: 5.0,
+            sensitivity: 0.002,
+            pitch_limit: 89f32.to_radians(),
+            strafe: AxisBinding::buttons(Key::A, Key::D),
+            lift: AxisBinding::buttons(Key::Shift, Key::Space),
+            forward: AxisBinding::buttons(Key::S, Key::W),
+            look_button: None,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+/// A third person camera that stays behind a moving target and smoothly catches up with it
+#[derive(Clone, Debug, PartialEq)]
+pub struct FollowCamera {
+    /// What to follow, this should be updated every frame
+    pub target: Point3,
+    /// Where the camera wants to be relative to the target, in world space
+    pub offset: Vec3,
+    /// How quickly the camera catches up, higher is snappier and `f32::INFINITY` sticks to the target (5.0 by default).
+    /// Negative or NaN damping is reset to 0.0 (with a warning), which leaves the camera where it is.
+    pub damping: f32,
+}
+
+impl FollowCamera {
+    pub fn new(offset: Vec3) -> Self {
+        Self {
+            offset,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for FollowCamera {
+    fn default() -> Self {
+        Self {
+            target: Point3::origin(),
+            offset: Vec3::new(0.0, 3.0, -6.0),
+            damping: 5.0,
+        }
+    }
+}
+
+impl CameraController {
+    pub fn mode(&self) -> &CameraMode {
+        &self.mode
+    }
+
+    pub fn mode_mut(&mut self) -> &mut CameraMode {
+        &mut self.mode
+    }
+
+    /// Changes what controls the camera. A fly camera starts out looking wherever the camera was looking, and a follow
+    /// camera starts from the camera's current position.
+    pub fn set_mode(&mut self, mut mode: CameraMode) {
+        if let CameraMode::Fly(fly) = &mut mode {
+            // target is only refreshed by update, the rotation is where the camera is really looking
+            let direction = self.look_target() - self.pos;
+            if direction.norm() > f32::EPSILON {
+                (fly.yaw, fly.pitch) = yaw_pitch(direction);
+            }
+        }
+        self.mode = mode;
+    }
+
+    /// Moves the camera according to its mode. RenoirApp calls this every frame after the main function, with the
+    /// unscaled delta time so that the camera still works while the game is paused.
+    pub fn update_mode(&mut self, input: &RenoirInput, delta_time: f32) {
+        // taken out so that the controller can be moved while the mode is read
+        let mut mode = std::mem::take(&mut self.mode);
+        match &mut mode {
+            CameraMode::Manual => {}
+            CameraMode::Orbit(orbit) => self.update_orbit(orbit, input),
+            CameraMode::Fly(fly) => self.update_fly(fly, input, delta_time),
+            CameraMode::Follow(follow) => self.update_follow(follow, delta_time),
+        }
+        self.mode = mode;
+    }
+
+    fn update_orbit(&mut self, orbit: &mut OrbitCamera, input: &RenoirInput) {
+        if orbit
+            .rotate_button
+            .is_none_or(|btn| input.mouse_pressed(btn))
+        {
+            let (dx, dy) = input.get_mouse_delta();
+            orbit.yaw -= dx * orbit.sensitivity;
+            orbit.pitch += dy * orbit.sensitivity;
+        }
+        orbit.pitch = orbit.pitch.clamp(-orbit.pitch_limit, orbit.pitch_limit);
+
+        // zooming by a fraction of the distance feels the same up close as it does far away
+        let (_, scroll) = input.get_scroll_delta();
+        orbit.distance *= (1.0 - orbit.zoom_speed).powf(scroll);
+        orbit.distance = orbit.distance.clamp(orbit.min_distance, orbit.max_distance);
+
+        let pos = orbit.target + direction(orbit.yaw, orbit.pitch) * orbit.distance;
+        self.look_from(pos, orbit.target);
+    }
+
+    fn update_fly(&mut self, fly: &mut FlyCamera, input: &RenoirInput, delta_time: f32) {
+        if fly.look_button.is_none_or(|btn| input.mouse_pressed(btn)) {
+            let (dx, dy) = input.get_mouse_delta();
+            fly.yaw -= dx * fly.sensitivity;
+            fly.pitch -= dy * fly.sensitivity;
+        }
+        fly.pitch = fly.pitch.clamp(-fly.pitch_limit, fly.pitch_limit);
+
+        let forward = direction(fly.yaw, fly.pitch);
+        let right = forward.cross(&self.up).normalize();
+
+        let movement = right * input.axis_binding_value(&fly.strafe)
+            + self.up * input.axis_binding_value(&fly.lift)
+            + forward * input.axis_binding_value(&fly.forward);
+        // so that moving diagonally isn't faster
+        let movement = if movement.norm() > 1.0 {
+            movement.normalize()
+        } else {
+            movement
+        };
+
+        let pos = self.pos + movement * fly.speed * delta_time;
+        self.look_from(pos, pos + forward);
+    }
+
+    fn update_follow(&mut self, follow: &mut FollowCamera, delta_time: f32) {
+        // reset rather than skipped every frame, so that the warning only shows up once
+        if follow.damping.is_nan() || follow.damping < 0.0 {
+            log::warn!(
+                "invalid follow camera damping {}, resetting it to 0.0",
+                follow.damping
+            );
+            follow.damping = 0.0;
+        }
+
+        // framerate independent smoothing, the camera covers the same fraction of the distance every second. Infinite
+        // damping is handled separately, as it would be NaN on frames where no time passes (infinity * 0)
+        let t = if follow.damping == f32::INFINITY {
+            1.0
+        } else {
+            1.0 - (-follow.damping * delta_time.max(0.0)).exp()
+        };
+        let goal = follow.target + follow.offset;
+        let pos = self.pos + (goal - self.pos) * t;
+        self.look_from(pos, follow.target);
+    }
+}
+
+// the direction from yaw and pitch, where yaw 0 is +Z and positive pitch is up
+fn direction(yaw: f32, pitch: f32) -> Vec3 {
+    Vec3::new(
+        pitch.cos() * yaw.sin(),
+        pitch.sin(),
+        pitch.cos() * yaw.cos(),
+    )
+}
+
+fn yaw_pitch(direction: Vec3) -> (f32, f32) {
+    let direction = direction.normalize();
+    (
+        direction.x.atan2(direction.z),
+        direction.y.clamp(-1.0, 1.0).asin(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follow(damping: f32, delta_time: f32) -> CameraController {
+        let mut camera = CameraController::new();
+        camera.set_mode(CameraMode::Follow(FollowCamera {
+            target: Point3::new(10.0, 0.0, 0.0),
+            offset: Vec3::new(0.0, 0.0, -5.0),
+            damping,
+        }));
+        camera.update_mode(&RenoirInput::new(), delta_time);
+        camera
+    }
+
+    #[test]
+    fn infinite_damping_sticks_to_the_target_even_without_time_passing() {
+        for delta_time in [0.0, 0.016] {
+            let camera = follow(f32::INFINITY, delta_time);
+            assert_eq!(camera.pos, Point3::new(10.0, 0.0, -5.0));
+        }
+    }
+
+    #[test]
+    fn finite_damping_stays_put_without_time_passing() {
+        let start = CameraController::new().pos;
+        assert_eq!(follow(5.0, 0.0).pos, start);
+
+        let moved = follow(5.0, 0.1).pos;
+        assert!(moved != start && moved.coords.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn invalid_damping_leaves_the_camera_where_it_is() {
+        let start = CameraController::new().pos;
+        for damping in [f32::NAN, -1.0] {
+            let camera = follow(damping, 0.016);
+            assert_eq!(camera.pos, start);
+
+            let CameraMode::Follow(follow) = camera.mode() else {
+                unreachable!()
+            };
+            assert_eq!(follow.damping, 0.0);
+        }
+    }
+
+    fn look_direction(camera: &CameraController) -> Vec3 {
+        (camera.look_target() - camera.pos).normalize()
+    }
+
+    #[test]
+    fn fly_camera_starts_looking_where_the_camera_was() {
+        // a fresh camera looks down +Z, whatever its target says
+        let mut camera = CameraController::new();
+        camera.set_mode(CameraMode::Fly(FlyCamera::new()));
+        camera.update_mode(&RenoirInput::new(), 0.016);
+        assert!((look_direction(&camera) - Vec3::z()).norm() < 1e-4);
+
+        // moving the camera after turning it leaves its target behind
+        let mut camera = CameraController::new();
+        camera.rotate_y(0.5);
+        camera.rotate_x(-0.3);
+        camera.set_translate(5.0, 2.0, 1.0);
+        let before = look_direction(&camera);
+        assert!((before - Vec3::z()).norm() > 0.1);
+
+        camera.set_mode(CameraMode::Fly(FlyCamera::new()));
+        camera.update_mode(&RenoirInput::new(), 0.016);
+        assert!((look_direction(&camera) - before).norm() < 1e-4);
+    }
+
+    #[test]
+    fn fly_camera_at_the_origin_isnt_nan() {
+        let mut camera = CameraController::new();
+        camera.set_translate(0.0, 0.0, 0.0);
+        camera.set_mode(CameraMode::Fly(FlyCamera::new()));
+        camera.update_mode(&RenoirInput::new(), 0.016);
+
+        assert_eq!(camera.pos, Point3::origin());
+        assert!((look_direction(&camera) - Vec3::z()).norm() < 1e-4);
+    }
+
+    #[test]
+    fn fly_camera_moves_forward_at_its_speed() {
+        // a fresh camera looks down +Z
+        let mut camera = CameraController::new();
+        camera.set_translate(0.0, 0.0, 0.0);
+        camera.set_mode(CameraMode::Fly(FlyCamera::new()));
+
+        let mut input = RenoirInput::new();
+        input.press_key(Key::W);
+        input.press_key(Key::D);
+        camera.update_mode(&input, 0.5);
+
+        // diagonal movement isn't any faster than straight movement
+        assert!((camera.pos.coords.norm() - 2.5).abs() < 1e-4);
+        assert!(camera.pos.z > 0.0);
+    }
+}
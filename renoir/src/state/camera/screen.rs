@@ -0,0 +1,149 @@
+use crate::math::prelude::*;
+
+use super::CameraController;
+
+/// A line going one way from a point, e.g. from the camera through the cursor for picking things with the mouse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Point3,
+    /// Always normalized
+    pub direction: Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: Point3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// The point `distance` units along the ray
+    pub fn at(&self, distance: f32) -> Point3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Where the ray hits a plane (given by any point on it and its normal), None if it never does
+    pub fn intersect_plane(&self, point: Point3, normal: Vec3) -> Option<Point3> {
+        let facing = self.direction.dot(&normal);
+        if facing.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let distance = (point - self.origin).dot(&normal) / facing;
+        (distance >= 0.0).then(|| self.at(distance))
+    }
+}
+
+/// Going between screen positions and the world. Screen positions are in pixels from the top left of the window,
+/// the same as `RenoirInput::get_cursor_position`, even for cameras that only draw to part of it (see `Viewport`). The
+/// screen size is usually `WindowInfo::size`:
+/// ```
+/// # use renoir::prelude::*;
+/// let mut camera = CameraController::new();
+/// camera.set_translate(0.0, 5.0, -10.0);
+///
+/// // the cursor in the middle of an 800x600 window
+/// let ray = camera.screen_to_ray((400.0, 300.0), (800, 600)).unwrap();
+/// let ground = ray.intersect_plane(Point3::origin(), Vec3::y());
+///
+/// let screen = camera.world_to_screen(Point3::new(0.0, 5.0, 0.0), (800, 600)).unwrap();
+/// assert!((screen.0 - 400.0).abs() < 0.01 && (screen.1 - 300.0).abs() < 0.01);
+/// ```
+impl CameraController {
+    /// The ray from the camera through a point on the screen. It starts on the near clip plane, so nothing behind the
+    /// camera is ever hit. None if the camera's viewport has no size.
+    pub fn screen_to_ray(&self, screen_pos: (f32, f32), screen_size: (u32, u32)) -> Option<Ray> {
+        let (left, top, width, height) = self.viewport_rect(screen_size)?;
+        let inverse = self.view_projection_matrix(width, height).try_inverse()?;
+
+        // wgpu's clip space has y going up and depth going from 0.0 at the near plane to 1.0 at the far one
+        let x = (screen_pos.0 - left) / width * 2.0 - 1.0;
+        let y = 1.0 - (screen_pos.1 - top) / height * 2.0;
+        let near = inverse.transform_point(&Point3::new(x, y, 0.0));
+        let far = inverse.transform_point(&Point3::new(x, y, 1.0));
+
+        Some(Ray::new(near, far - near))
+    }
+
+    /// Where a point in the world shows up on the screen. Points outside of the viewport still get a position (which
+    /// is handy for pointing at things that are out of view), but points behind the camera's near plane are None, with
+    /// either projection.
+    pub fn world_to_screen(&self, point: Point3, screen_size: (u32, u32)) -> Option<(f32, f32)> {
+        let (left, top, width, height) = self.viewport_rect(screen_size)?;
+
+        // w is always 1 for orthographic cameras, so the depth is what tells whether the point is behind the camera
+        let clip = self.view_projection_matrix(width, height) * point.to_homogeneous();
+        if clip.w <= 0.0 || clip.z < 0.0 {
+            return None;
+        }
+
+        let (x, y) = (clip.x / clip.w, clip.y / clip.w);
+        Some((
+            left + (x + 1.0) * 0.5 * width,
+            top + (1.0 - y) * 0.5 * height,
+        ))
+    }
+
+    // the same pixels the renderer draws this camera to
+    fn viewport_rect(&self, screen_size: (u32, u32)) -> Option<(f32, f32, f32, f32)> {
+        let (left, top, width, height) = self.viewport.pixel_rect(screen_size)?;
+        Some((left as f32, top as f32, width as f32, height as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::camera::{OrthographicSize, Projection};
+
+    // turned a quarter turn and moved away from the origin, so that the view matrix actually does something
+    fn camera(projection: Projection) -> CameraController {
+        let mut camera = CameraController::new();
+        camera.set_translate(3.0, 1.0, -2.0);
+        camera.rotate_y(std::f32::consts::FRAC_PI_2);
+        camera.projection = projection;
+        camera
+    }
+
+    fn forward(camera: &CameraController) -> Vec3 {
+        (camera.look_target() - camera.pos).normalize()
+    }
+
+    #[test]
+    fn points_behind_the_camera_are_not_on_screen() {
+        for projection in [
+            Projection::Perspective,
+            Projection::Orthographic(OrthographicSize::Height(10.0)),
+        ] {
+            let camera = camera(projection);
+            let forward = forward(&camera);
+            assert!(
+                forward.x.abs() > 0.99,
+                "the camera should be looking along X"
+            );
+
+            let ahead = camera.world_to_screen(camera.pos + forward * 5.0, (800, 600));
+            let (x, y) = ahead.unwrap();
+            assert!((x - 400.0).abs() < 0.01 && (y - 300.0).abs() < 0.01);
+
+            // up is still up
+            let above = camera.world_to_screen(camera.pos + forward * 5.0 + Vec3::y(), (800, 600));
+            assert!(above.unwrap().1 < 300.0);
+
+            let behind = camera.world_to_screen(camera.pos - forward * 5.0, (800, 600));
+            assert_eq!(behind, None, "{projection:?}");
+        }
+    }
+
+    #[test]
+    fn rays_go_back_through_where_points_are_drawn() {
+        let camera = camera(Projection::Perspective);
+        let point = camera.pos + forward(&camera) * 8.0 + Vec3::new(0.0, -2.0, 1.0);
+
+        let screen = camera.world_to_screen(point, (800, 600)).unwrap();
+        let ray = camera.screen_to_ray(screen, (800, 600)).unwrap();
+        let to_point = (point - ray.origin).normalize();
+        assert!((ray.direction - to_point).norm() < 1e-4);
+    }
+}
@@ -0,0 +1,60 @@
+/// The part of the window a camera draws to, in fractions of the window's size from its top left corner, so it
+/// stays the same when the window is resized
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    pub const FULL: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// One cell of a grid of same sized viewports, counting left to right and then top to bottom. For split-screen,
+    /// `Viewport::grid(2, 1, 0)` is the left half of the window and `Viewport::grid(2, 2, 3)` the bottom right quarter.
+    pub fn grid(columns: u32, rows: u32, index: u32) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let (width, height) = (1.0 / columns as f32, 1.0 / rows as f32);
+
+        Self {
+            x: (index % columns) as f32 * width,
+            y: (index / columns) as f32 * height,
+            width,
+            height,
+        }
+    }
+
+    // the viewport in whole pixels as (x, y, width, height), cut down to fit in the window. None if none of it is in
+    // the window, as there's nothing to draw then
+    pub(crate) fn pixel_rect(&self, window_size: (u32, u32)) -> Option<(u32, u32, u32, u32)> {
+        let (width, height) = (window_size.0 as f32, window_size.1 as f32);
+
+        let left = (self.x * width).round().clamp(0.0, width) as u32;
+        let top = (self.y * height).round().clamp(0.0, height) as u32;
+        let right = ((self.x + self.width) * width).round().clamp(0.0, width) as u32;
+        let bottom = ((self.y + self.height) * height).round().clamp(0.0, height) as u32;
+
+        (right > left && bottom > top).then(|| (left, top, right - left, bottom - top))
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+
+// how many frames can wait to be written before recorded frames start getting dropped, which keeps memory use bounded
+// when encoding can't keep up with the frame rate
+const QUEUE_SIZE: usize = 4;
+
+/// Saves rendered frames to PNG files, either once (screenshots) or every frame (recording). Frames are copied from
+/// the renderer after it has drawn them and written to disk on a separate thread, so the game doesn't stall on encoding.
+/// If that thread falls behind while recording, frames are dropped (and logged) rather than queued up.
+#[derive(Default)]
+pub struct FrameCapture {
+    screenshots: Vec<PathBuf>,
+    recording: Option<Recording>,
+    worker: Option<Worker>,
+}
+
+struct Recording {
+    dir: PathBuf,
+    next_frame: u32,
+}
+
+struct Job {
+    frame: image::RgbaImage,
+    paths: Vec<PathBuf>,
+}
+
+/// The thread that encodes and writes frames, in the order they were captured.
+struct Worker {
+    sender: SyncSender<Job>,
+    thread: JoinHandle<()>,
+}
+
+impl Worker {
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<Job>(QUEUE_SIZE);
+        let thread = std::thread::spawn(move || {
+            for job in receiver {
+                for path in job.paths {
+                    if let Err(e) = save_png(&job.frame, &path) {
+                        log::error!("failed to save frame to {}: {e}", path.display());
+                    }
+                }
+            }
+        });
+
+        Self { sender, thread }
+    }
+
+    // waits for every frame that's already queued to be written
+    fn finish(self) {
+        drop(self.sender);
+        if self.thread.join().is_err() {
+            log::error!("the frame capture thread panicked, some frames may not have been saved");
+        }
+    }
+}
+
+impl FrameCapture {
+    pub fn new() -> Self {
+        // can't use struct update syntax here, as FrameCapture implements Drop
+        Self::default()
+    }
+
+    /// Saves the next rendered frame to `path` as a PNG.
+    pub fn screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.screenshots.push(path.into());
+    }
+
+    /// Saves every rendered frame into `dir` as numbered PNGs (frame_00000.png, frame_00001.png, ...) until
+    /// `stop_recording` is called. The directory is created if it doesn't exist.
+    pub fn start_recording(&mut self, dir: impl Into<PathBuf>) {
+        self.recording = Some(Recording {
+            dir: dir.into(),
+            next_frame: 0,
+        });
+    }
+
+    /// Stops recording, and waits for the frames that are still queued to be written.
+    pub fn stop_recording(&mut self) {
+        self.recording = None;
+        if let Some(worker) = self.worker.take() {
+            worker.finish();
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub(crate) fn wants_frame(&self) -> bool {
+        !self.screenshots.is_empty() || self.recording.is_some()
+    }
+
+    // called by the RenoirApp with the frame the renderer captured
+    pub(crate) fn save(&mut self, frame: image::RgbaImage) {
+        let mut paths = std::mem::take(&mut self.screenshots);
+        let has_screenshots = !paths.is_empty();
+
+        if let Some(recording) = self.recording.as_ref() {
+            paths.push(
+                recording
+                    .dir
+                    .join(format!("frame_{:05}.png", recording.next_frame)),
+            );
+        }
+
+        let sender = &self.worker.get_or_insert_with(Worker::new).sender;
+        let job = Job { frame, paths };
+
+        // screenshots were asked for explicitly so they're worth waiting for, recorded frames aren't
+        let result = if has_screenshots {
+            sender.send(job).map_err(|_| "the thread has stopped")
+        } else {
+            sender.try_send(job).map_err(|e| match e {
+                TrySendError::Full(_) => "saving frames is falling behind",
+                TrySendError::Disconnected(_) => "the thread has stopped",
+            })
+        };
+
+        match result {
+            Ok(()) => {
+                // numbered only once they're actually saved, so recordings don't have gaps
+                if let Some(recording) = self.recording.as_mut() {
+                    recording.next_frame += 1;
+                }
+            }
+            Err(reason) => log::warn!("dropped a captured frame, {reason}"),
+        }
+    }
+
+    // called by the RenoirApp when the renderer couldn't copy the frame, which won't get any better by trying again
+    pub(crate) fn fail(&mut self, error: anyhow::Error) {
+        log::error!(
+            "failed to capture frame, dropping screenshots and stopping recording: {error:#}"
+        );
+        self.screenshots.clear();
+        self.recording = None;
+    }
+}
+
+/// Waits for queued frames to be written, so the last screenshots and recorded frames aren't lost when the app exits.
+impl Drop for FrameCapture {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.finish();
+        }
+    }
+}
+
+fn save_png(frame: &image::RgbaImage, path: &Path) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    frame.save_with_format(path, image::ImageFormat::Png)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_frames_are_written_by_the_time_recording_stops() {
+        let dir = std::env::temp_dir().join(format!("renoir_capture_{}", std::process::id()));
+        let frame = image::RgbaImage::from_pixel(2, 2, image::Rgba([1, 2, 3, 255]));
+
+        let mut capture = FrameCapture::new();
+        capture.start_recording(&dir);
+        capture.screenshot(dir.join("screenshot.png"));
+        // screenshots are never dropped, even if the queue is full
+        for _ in 0..QUEUE_SIZE * 4 {
+            assert!(capture.wants_frame());
+            capture.save(frame.clone());
+        }
+        capture.stop_recording();
+
+        assert!(!capture.wants_frame());
+        assert_eq!(
+            image::open(dir.join("screenshot.png")).unwrap().to_rgba8(),
+            frame
+        );
+
+        // frames can be dropped when writing falls behind, but the ones that were saved are numbered without gaps
+        let saved = std::fs::read_dir(&dir).unwrap().count() - 1;
+        assert!(saved > 0);
+        for i in 0..saved {
+            assert!(dir.join(format!("frame_{i:05}.png")).exists());
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// A rolling window of timings in seconds, used by FrameStats. All the getters return 0.0 while there are no samples.
+#[derive(Clone, Debug, Default)]
+pub struct TimingSamples {
+    samples: VecDeque<f32>,
+}
+
+impl TimingSamples {
+    fn push(&mut self, seconds: f32, window_size: usize) {
+        self.samples.push_back(seconds);
+        self.truncate(window_size);
+    }
+
+    fn truncate(&mut self, window_size: usize) {
+        while self.samples.len() > window_size {
+            self.samples.pop_front();
+        }
+    }
+
+    fn sorted(&self) -> Vec<f32> {
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_by(f32::total_cmp);
+        sorted
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The most recent sample
+    pub fn last(&self) -> f32 {
+        self.samples.back().copied().unwrap_or(0.0)
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    pub fn min(&self) -> f32 {
+        self.samples.iter().copied().reduce(f32::min).unwrap_or(0.0)
+    }
+
+    pub fn max(&self) -> f32 {
+        self.samples.iter().copied().reduce(f32::max).unwrap_or(0.0)
+    }
+
+    /// The sample that `percentile` percent of samples are less than or equal to, e.g. `percentile(99.0)` is the
+    /// time that only 1% of samples took longer than. `percentile` is clamped to 0..=100.
+    pub fn percentile(&self, percentile: f32) -> f32 {
+        let sorted = self.sorted();
+        if sorted.is_empty() {
+            return 0.0;
+        }
+
+        // nearest-rank method
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    /// The average of the slowest `percent` percent of samples (always at least one sample)
+    pub fn slowest_average(&self, percent: f32) -> f32 {
+        let sorted = self.sorted();
+        if sorted.is_empty() {
+            return 0.0;
+        }
+
+        let count = ((percent.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize)
+            .clamp(1, sorted.len());
+        sorted[sorted.len() - count..].iter().sum::<f32>() / count as f32
+    }
+}
+
+/// Timing statistics over the last few frames (240 by default), for FPS counters and tracking down stutters.
+/// Frame times come from `DeltaTime::raw_delta_time`, so they aren't clamped to `max_delta_time` and show how long a
+/// spike really was. The run and render timings are the CPU time spent in the main function and the renderer
+/// respectively.
+pub struct FrameStats {
+    window_size: usize,
+    frame_time: TimingSamples,
+    run_time: TimingSamples,
+    render_time: TimingSamples,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Sets how many frames the statistics are calculated over
+    pub fn set_window_size(&mut self, frames: usize) {
+        self.window_size = frames.max(1);
+        self.frame_time.truncate(self.window_size);
+        self.run_time.truncate(self.window_size);
+        self.render_time.truncate(self.window_size);
+    }
+
+    /// Time between frames, in seconds
+    pub fn frame_time(&self) -> &TimingSamples {
+        &self.frame_time
+    }
+
+    /// CPU time spent in the main (run) function each frame, in seconds
+    pub fn run_time(&self) -> &TimingSamples {
+        &self.run_time
+    }
+
+    /// CPU time spent in the renderer each frame, in seconds
+    pub fn render_time(&self) -> &TimingSamples {
+        &self.render_time
+    }
+
+    pub fn average_fps(&self) -> f32 {
+        fps(self.frame_time.average())
+    }
+
+    /// The FPS of the fastest frame in the window
+    pub fn max_fps(&self) -> f32 {
+        fps(self.frame_time.min())
+    }
+
+    /// The FPS of the slowest frame in the window
+    pub fn min_fps(&self) -> f32 {
+        fps(self.frame_time.max())
+    }
+
+    /// The average FPS of the slowest 1% of frames
+    pub fn one_percent_low_fps(&self) -> f32 {
+        fps(self.frame_time.slowest_average(1.0))
+    }
+
+    pub(crate) fn record_frame_time(&mut self, seconds: f32) {
+        self.frame_time.push(seconds, self.window_size);
+    }
+
+    pub(crate) fn record_run_time(&mut self, duration: Duration) {
+        self.run_time.push(duration.as_secs_f32(), self.window_size);
+    }
+
+    pub(crate) fn record_render_time(&mut self, duration: Duration) {
+        self.render_time
+            .push(duration.as_secs_f32(), self.window_size);
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self {
+            window_size: 240,
+            frame_time: TimingSamples::default(),
+            run_time: TimingSamples::default(),
+            render_time: TimingSamples::default(),
+        }
+    }
+}
+
+fn fps(frame_time: f32) -> f32 {
+    if frame_time > 0.0 {
+        1.0 / frame_time
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_and_worst_frame() {
+        let mut stats = FrameStats::new();
+        for _ in 0..99 {
+            stats.record_frame_time(0.01);
+        }
+        // a hitch longer than DeltaTime's max_delta_time still shows up as it was
+        stats.record_frame_time(0.5);
+
+        let frame_time = stats.frame_time();
+        assert_eq!(frame_time.len(), 100);
+        assert_eq!(frame_time.max(), 0.5);
+        assert_eq!(frame_time.min(), 0.01);
+        assert_eq!(frame_time.percentile(99.0), 0.01);
+        assert_eq!(frame_time.percentile(100.0), 0.5);
+        assert_eq!(stats.min_fps(), 2.0);
+    }
+
+    #[test]
+    fn old_samples_leave_the_window() {
+        let mut stats = FrameStats::new();
+        stats.set_window_size(2);
+        stats.record_frame_time(1.0);
+        stats.record_frame_time(0.1);
+        stats.record_frame_time(0.2);
+
+        assert_eq!(stats.frame_time().len(), 2);
+        assert_eq!(stats.frame_time().max(), 0.2);
+    }
+}
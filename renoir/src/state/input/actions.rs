@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use super::{GamepadAxis, GamepadButton, Key, KeyState, MouseBtn, RenoirInput};
+
+/// Something that can trigger an action
+#[derive(Clone, Debug)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseBtn),
+    /// A button on any connected gamepad
+    Gamepad(GamepadButton),
+    /// A key that only counts while all of the modifiers are held too, e.g. Ctrl+S. Like most engines, the keys can
+    /// be pressed in any order. The order the modifiers are listed in doesn't matter either, Ctrl+Shift+S and
+    /// Shift+Ctrl+S are the same binding.
+    Chord {
+        modifiers: Vec<Key>,
+        key: Key,
+    },
+}
+
+impl Binding {
+    pub fn chord(modifiers: impl IntoIterator<Item = Key>, key: Key) -> Self {
+        Self::Chord {
+            modifiers: modifiers.into_iter().collect(),
+            key,
+        }
+    }
+}
+
+impl PartialEq for Binding {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Key(a), Self::Key(b)) => a == b,
+            (Self::Mouse(a), Self::Mouse(b)) => a == b,
+            (Self::Gamepad(a), Self::Gamepad(b)) => a == b,
+            (
+                Self::Chord { modifiers, key },
+                Self::Chord {
+                    modifiers: other_modifiers,
+                    key: other_key,
+                },
+            ) => {
+                key == other_key && sorted_modifiers(modifiers) == sorted_modifiers(other_modifiers)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Binding {}
+
+// has to agree with PartialEq, so chords hash their modifiers in a fixed order
+impl Hash for Binding {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Key(key) => key.hash(state),
+            Self::Mouse(btn) => btn.hash(state),
+            Self::Gamepad(button) => button.hash(state),
+            Self::Chord { modifiers, key } => {
+                sorted_modifiers(modifiers).hash(state);
+                key.hash(state);
+            }
+        }
+    }
+}
+
+// modifiers as a set, so that neither their order nor listing one twice changes which binding a chord is
+fn sorted_modifiers(modifiers: &[Key]) -> Vec<usize> {
+    let mut modifiers = modifiers
+        .iter()
+        .map(|&key| key as usize)
+        .collect::<Vec<_>>();
+    modifiers.sort_unstable();
+    modifiers.dedup();
+    modifiers
+}
+
+impl From<Key> for Binding {
+    fn from(key: Key) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl From<MouseBtn> for Binding {
+    fn from(btn: MouseBtn) -> Self {
+        Self::Mouse(btn)
+    }
+}
+
+impl From<GamepadButton> for Binding {
+    fn from(button: GamepadButton) -> Self {
+        Self::Gamepad(button)
+    }
+}
+
+/// Something that drives an axis
+#[derive(Clone, Debug, PartialEq)]
+pub enum AxisBinding {
+    /// -1.0 while `negative` is pressed and 1.0 while `positive` is (0.0 if both are)
+    Buttons {
+        negative: Binding,
+        positive: Binding,
+    },
+    /// How far the mouse moved this frame, multiplied by `scale`
+    MouseX {
+        scale: f32,
+    },
+    MouseY {
+        scale: f32,
+    },
+    /// How far the mouse wheel scrolled this frame, multiplied by `scale`
+    ScrollX {
+        scale: f32,
+    },
+    ScrollY {
+        scale: f32,
+    },
+    /// A stick or trigger on any connected gamepad (with the deadzone applied), multiplied by `scale`
+    Gamepad {
+        axis: GamepadAxis,
+        scale: f32,
+    },
+}
+
+impl AxisBinding {
+    pub fn buttons(negative: impl Into<Binding>, positive: impl Into<Binding>) -> Self {
+        Self::Buttons {
+            negative: negative.into(),
+            positive: positive.into(),
+        }
+    }
+
+    // buttons are clamped together so that two key pairs on the same axis can't add up to more than 1.0
+    fn is_digital(&self) -> bool {
+        matches!(self, Self::Buttons { .. })
+    }
+}
+
+/// Named actions ("jump") and axes ("move_x") and what they're bound to, so that gameplay code never has to refer to
+/// specific keys. It lives on RenoirInput, which is also where actions are queried:
+/// ```
+/// # use renoir::prelude::*;
+/// let mut input = RenoirInput::new();
+/// input.actions.bind("jump", Key::Space);
+/// input.actions.bind("jump", MouseBtn::Right);
+/// input.actions.bind("save", Binding::chord([Key::Ctrl], Key::S));
+/// input.actions.bind_axis("move_x", AxisBinding::buttons(Key::A, Key::D));
+///
+/// assert!(!input.action_pressed("jump"));
+/// assert_eq!(input.axis("move_x"), 0.0);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ActionMap {
+    actions: HashMap<String, Vec<Binding>>,
+    axes: HashMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Adds a binding to an action, an action can have any number of bindings and is pressed if any of them are
+    pub fn bind(&mut self, action: impl Into<String>, binding: impl Into<Binding>) {
+        let bindings = self.actions.entry(action.into()).or_default();
+        let binding = binding.into();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Adds a binding to an axis, the axis' value is the sum of all of its bindings
+    pub fn bind_axis(&mut self, axis: impl Into<String>, binding: AxisBinding) {
+        let bindings = self.axes.entry(axis.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes every binding from an action
+    pub fn unbind(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    /// Removes every binding from an axis
+    pub fn unbind_axis(&mut self, axis: &str) {
+        self.axes.remove(axis);
+    }
+
+    /// Replaces one of an action's bindings, e.g. when a player picks a new key in a controls menu. Returns false (and
+    /// changes nothing) if `old` isn't bound to the action. Use `save` to persist the change.
+    pub fn rebind(&mut self, action: &str, old: &Binding, new: impl Into<Binding>) -> bool {
+        let Some(bindings) = self.actions.get_mut(action) else {
+            return false;
+        };
+        let Some(index) = bindings.iter().position(|binding| binding == old) else {
+            return false;
+        };
+
+        let new = new.into();
+        if bindings.contains(&new) {
+            bindings.remove(index);
+        } else {
+            bindings[index] = new;
+        }
+        true
+    }
+
+    /// Like `rebind`, but for axes
+    pub fn rebind_axis(&mut self, axis: &str, old: &AxisBinding, new: AxisBinding) -> bool {
+        let Some(bindings) = self.axes.get_mut(axis) else {
+            return false;
+        };
+        let Some(index) = bindings.iter().position(|binding| binding == old) else {
+            return false;
+        };
+
+        if bindings.contains(&new) {
+            bindings.remove(index);
+        } else {
+            bindings[index] = new;
+        }
+        true
+    }
+
+    /// Every action that `binding` is bound to, handy for warning players before they rebind something
+    pub fn actions_using(&self, binding: &Binding) -> Vec<&str> {
+        let mut actions = self
+            .actions
+            .iter()
+            .filter(|(_, bindings)| bindings.contains(binding))
+            .map(|(action, _)| action.as_str())
+            .collect::<Vec<_>>();
+        actions.sort_unstable();
+        actions
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(String::as_str)
+    }
+}
+
+// what actions are read from, which is either all of the input or an input context's view of it
+pub(super) trait InputSource {
+    fn actions(&self) -> &ActionMap;
+    fn key(&self, key: Key) -> KeyState;
+    fn mouse_btn(&self, btn: MouseBtn) -> KeyState;
+    fn gamepad_btn(&self, button: GamepadButton) -> KeyState;
+    fn gamepad_axis(&self, axis: GamepadAxis) -> f32;
+    fn mouse_delta(&self) -> (f32, f32);
+    fn scroll_delta(&self) -> (f32, f32);
+
+    fn action_state(&self, action: &str) -> KeyState {
+        let states = self
+            .actions()
+            .bindings(action)
+            .iter()
+            .map(|binding| self.binding_state(binding));
+
+        combine(states)
+    }
+
+    fn axis_value(&self, axis: &str) -> f32 {
+        let bindings = self.actions().axis_bindings(axis);
+
+        let digital = bindings
+            .iter()
+            .filter(|binding| binding.is_digital())
+            .map(|binding| self.axis_binding_value(binding))
+            .sum::<f32>()
+            .clamp(-1.0, 1.0);
+        let analog = bindings
+            .iter()
+            .filter(|binding| !binding.is_digital())
+            .map(|binding| self.axis_binding_value(binding))
+            .sum::<f32>();
+
+        digital + analog
+    }
+
+    fn binding_state(&self, binding: &Binding) -> KeyState {
+        match binding {
+            Binding::Key(key) => self.key(*key),
+            Binding::Mouse(btn) => self.mouse_btn(*btn),
+            Binding::Gamepad(button) => self.gamepad_btn(*button),
+            Binding::Chord { modifiers, key } => {
+                let key = self.key(*key);
+                let modifiers = modifiers.iter().map(|modifier| self.key(*modifier));
+
+                let was_pressed = key.was_pressed() && modifiers.clone().all(|m| m.was_pressed());
+                let pressed = key.pressed() && modifiers.clone().all(|m| m.pressed());
+                KeyState::from_transition(was_pressed, pressed)
+            }
+        }
+    }
+
+    fn axis_binding_value(&self, binding: &AxisBinding) -> f32 {
+        match binding {
+            AxisBinding::Buttons { negative, positive } => {
+                self.binding_state(positive).pressed() as i32 as f32
+                    - self.binding_state(negative).pressed() as i32 as f32
+            }
+            AxisBinding::MouseX { scale } => self.mouse_delta().0 * scale,
+            AxisBinding::MouseY { scale } => self.mouse_delta().1 * scale,
+            AxisBinding::ScrollX { scale } => self.scroll_delta().0 * scale,
+            AxisBinding::ScrollY { scale } => self.scroll_delta().1 * scale,
+            AxisBinding::Gamepad { axis, scale } => self.gamepad_axis(*axis) * scale,
+        }
+    }
+}
+
+impl InputSource for RenoirInput {
+    fn actions(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    fn key(&self, key: Key) -> KeyState {
+        self.get_key(key)
+    }
+
+    fn mouse_btn(&self, btn: MouseBtn) -> KeyState {
+        self.get_mouse_btn(btn)
+    }
+
+    fn gamepad_btn(&self, button: GamepadButton) -> KeyState {
+        self.any_gamepad_btn(button)
+    }
+
+    fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.any_gamepad_axis(axis)
+    }
+
+    fn mouse_delta(&self) -> (f32, f32) {
+        self.mouse.cursor_delta
+    }
+
+    fn scroll_delta(&self) -> (f32, f32) {
+        self.mouse.scroll_delta
+    }
+}
+
+impl RenoirInput {
+    /// The combined state of all of an action's bindings, an action that doesn't exist is always Released
+    pub fn action(&self, action: &str) -> KeyState {
+        self.action_state(action)
+    }
+
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.action(action).pressed()
+    }
+
+    pub fn action_just_pressed(&self, action: &str) -> bool {
+        self.action(action).just_pressed()
+    }
+
+    pub fn action_released(&self, action: &str) -> bool {
+        self.action(action).released()
+    }
+
+    pub fn action_just_released(&self, action: &str) -> bool {
+        self.action(action).just_released()
+    }
+
+    /// The value of an axis this frame, an axis that doesn't exist is always 0.0
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axis_value(axis)
+    }
+
+    pub fn binding_state(&self, binding: &Binding) -> KeyState {
+        InputSource::binding_state(self, binding)
+    }
+
+    /// The value of a single axis binding, without going through the ActionMap
+    pub fn axis_binding_value(&self, binding: &AxisBinding) -> f32 {
+        InputSource::axis_binding_value(self, binding)
+    }
+}
+
+// an action is pressed while any of its bindings are, so it's only just pressed/released when the first binding is
+// pressed or the last one is released
+pub(super) fn combine(states: impl Iterator<Item = KeyState>) -> KeyState {
+    let (was_pressed, pressed) = states.fold((false, false), |(was, is), state| {
+        (was || state.was_pressed(), is || state.pressed())
+    });
+    KeyState::from_transition(was_pressed, pressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::input::{GamepadId, VirtualGamepad};
+
+    #[test]
+    fn chords_fire_on_the_last_key_pressed() {
+        let mut input = RenoirInput::new();
+        let save = Binding::chord([Key::Ctrl], Key::S);
+        input.actions.bind("save", save.clone());
+
+        // the key on its own, then with the modifier added, either order counts
+        input.press_key(Key::S);
+        assert!(input.action_released("save"));
+        input.update();
+        input.press_key(Key::Ctrl);
+        assert!(input.action_just_pressed("save"));
+        input.update();
+        assert_eq!(input.action("save"), KeyState::Pressed);
+
+        // letting go of the modifier lets go of the action, even with the key still held
+        input.release_key(Key::Ctrl);
+        assert!(input.action_just_released("save"));
+        assert!(input.pressed(Key::S));
+        input.update();
+        assert_eq!(input.binding_state(&save), KeyState::Released);
+    }
+
+    #[test]
+    fn opposing_axis_keys_cancel_out() {
+        let mut input = RenoirInput::new();
+        input
+            .actions
+            .bind_axis("move_x", AxisBinding::buttons(Key::A, Key::D));
+        input.actions.bind_axis(
+            "move_x",
+            AxisBinding::buttons(Key::ArrowLeft, Key::ArrowRight),
+        );
+
+        input.press_key(Key::D);
+        assert_eq!(input.axis("move_x"), 1.0);
+        // two bindings pushing the same way are still clamped to 1.0
+        input.press_key(Key::ArrowRight);
+        assert_eq!(input.axis("move_x"), 1.0);
+
+        input.release_key(Key::ArrowRight);
+        input.press_key(Key::A);
+        assert_eq!(input.axis("move_x"), 0.0);
+    }
+
+    #[test]
+    fn analog_axes_are_added_on_top_of_buttons() {
+        let mut input = RenoirInput::new();
+        input.actions.bind_axis(
+            "look_x",
+            AxisBinding::buttons(Key::ArrowLeft, Key::ArrowRight),
+        );
+        input
+            .actions
+            .bind_axis("look_x", AxisBinding::MouseX { scale: 0.5 });
+
+        input.press_key(Key::ArrowRight);
+        input.move_mouse(4.0, 0.0);
+        assert_eq!(input.axis("look_x"), 3.0);
+        assert_eq!(input.axis("missing"), 0.0);
+    }
+
+    #[test]
+    fn actions_bound_to_a_key_and_a_gamepad_button() {
+        let pad = VirtualGamepad::new(GamepadId(0));
+        let mut input = RenoirInput::new();
+        input.add_gamepad_backend(pad.clone());
+        input.actions.bind("jump", Key::Space);
+        input.actions.bind("jump", GamepadButton::South);
+
+        pad.connect();
+        pad.press(GamepadButton::South);
+        input.poll_gamepads();
+        assert!(input.action_just_pressed("jump"));
+        input.update();
+
+        // pressing the key as well doesn't press the action again, and it stays pressed until both are released
+        input.press_key(Key::Space);
+        assert_eq!(input.action("jump"), KeyState::Pressed);
+        input.update();
+        pad.release(GamepadButton::South);
+        input.poll_gamepads();
+        assert_eq!(input.action("jump"), KeyState::Pressed);
+        input.update();
+        input.release_key(Key::Space);
+        assert!(input.action_just_released("jump"));
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::recording::create_parent_dir;
+use super::{ActionMap, AxisBinding, Binding, GamepadAxis, GamepadButton, Key, MouseBtn};
+
+// the on-disk layout of a bindings file, every binding is a string so the file stays easy to edit by hand:
+//
+// [actions]
+// jump = ["Space", "MouseRight"]
+// save = ["Ctrl+S"]
+//
+// [axes]
+// move_x = ["A/D", "ArrowLeft/ArrowRight", "GamepadLeftStickX"]
+// look_x = ["MouseX*0.01"]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct BindingsFile {
+    actions: BTreeMap<String, Vec<String>>,
+    axes: BTreeMap<String, Vec<String>>,
+}
+
+/// A binding that's used by more than one action or axis, which is usually a mistake (but not always, e.g. a key
+/// that both opens a menu and pauses the game).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindingConflict {
+    pub binding: Binding,
+    /// The actions and axes using the binding, sorted by name
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for BindingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is bound to {}", self.binding, self.names.join(", "))
+    }
+}
+
+impl ActionMap {
+    /// Reads bindings from a TOML file. Every unknown key or button name is reported in the error, not just the first
+    /// one, and conflicting bindings are logged as warnings (see `conflicts`).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read bindings from {}", path.display()))?;
+        Self::from_toml(&toml).with_context(|| format!("invalid bindings in {}", path.display()))
+    }
+
+    /// Writes the bindings to a TOML file, creating its directory if it doesn't exist
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        create_parent_dir(path)?;
+        std::fs::write(path, self.to_toml()?)
+            .with_context(|| format!("failed to save bindings to {}", path.display()))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let file: BindingsFile = toml::from_str(toml)?;
+
+        let mut map = ActionMap::new();
+        let mut errors = Vec::new();
+
+        for (action, bindings) in file.actions {
+            for binding in bindings {
+                match binding.parse::<Binding>() {
+                    Ok(binding) => map.bind(action.clone(), binding),
+                    Err(e) => errors.push(format!("action \"{action}\": {e}")),
+                }
+            }
+        }
+
+        for (axis, bindings) in file.axes {
+            for binding in bindings {
+                match binding.parse::<AxisBinding>() {
+                    Ok(binding) => map.bind_axis(axis.clone(), binding),
+                    Err(e) => errors.push(format!("axis \"{axis}\": {e}")),
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+
+        for conflict in map.conflicts() {
+            log::warn!("conflicting bindings: {conflict}");
+        }
+
+        Ok(map)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        let file = BindingsFile {
+            actions: self
+                .actions()
+                .map(|action| {
+                    let bindings = self.bindings(action).iter().map(Binding::to_string);
+                    (action.to_owned(), bindings.collect())
+                })
+                .collect(),
+            axes: self
+                .axes()
+                .map(|axis| {
+                    let bindings = self.axis_bindings(axis).iter().map(AxisBinding::to_string);
+                    (axis.to_owned(), bindings.collect())
+                })
+                .collect(),
+        };
+
+        Ok(toml::to_string_pretty(&file)?)
+    }
+
+    /// Every binding that's used by more than one action or axis (counting both buttons of an axis)
+    pub fn conflicts(&self) -> Vec<BindingConflict> {
+        let mut users: HashMap<&Binding, Vec<&str>> = HashMap::new();
+
+        for action in self.actions() {
+            for binding in self.bindings(action) {
+                users.entry(binding).or_default().push(action);
+            }
+        }
+
+        for axis in self.axes() {
+            for binding in self.axis_bindings(axis) {
+                if let AxisBinding::Buttons { negative, positive } = binding {
+                    users.entry(negative).or_default().push(axis);
+                    users.entry(positive).or_default().push(axis);
+                }
+            }
+        }
+
+        let mut conflicts = users
+            .into_iter()
+            .filter_map(|(binding, mut names)| {
+                names.sort_unstable();
+                names.dedup();
+                (names.len() > 1).then(|| BindingConflict {
+                    binding: binding.clone(),
+                    names: names.into_iter().map(str::to_owned).collect(),
+                })
+            })
+            .collect::<Vec<_>>();
+        conflicts.sort_by_key(|conflict| conflict.binding.to_string());
+        conflicts
+    }
+}
+
+/// Bindings are written as key names (`Space`), mouse and gamepad buttons prefixed with Mouse or Gamepad (`MouseLeft`,
+/// `GamepadSouth`) or chords joined with + (`Ctrl+Shift+S`). Names are case insensitive when parsing.
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key}"),
+            Binding::Mouse(btn) => write!(f, "Mouse{btn}"),
+            Binding::Gamepad(button) => write!(f, "Gamepad{button}"),
+            Binding::Chord { modifiers, key } => {
+                for modifier in modifiers {
+                    write!(f, "{modifier}+")?;
+                }
+                write!(f, "{key}")
+            }
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        if let Some((modifiers, key)) = s.rsplit_once('+') {
+            let modifiers = modifiers
+                .split('+')
+                .map(parse_key)
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(Binding::chord(modifiers, parse_key(key)?));
+        }
+
+        if let Some(btn) = strip_prefix_ignore_case(s, "Mouse") {
+            return btn
+                .parse::<MouseBtn>()
+                .map(Binding::Mouse)
+                .map_err(|_| anyhow!("unknown mouse button \"{s}\""));
+        }
+
+        if let Some(button) = strip_prefix_ignore_case(s, "Gamepad") {
+            return button
+                .parse::<GamepadButton>()
+                .map(Binding::Gamepad)
+                .map_err(|_| anyhow!("unknown gamepad button \"{s}\""));
+        }
+
+        parse_key(s).map(Binding::Key)
+    }
+}
+
+/// Axes are written as two bindings separated by a / (`A/D`, negative first), or as `MouseX`, `MouseY`, `ScrollX`,
+/// `ScrollY` or a gamepad axis prefixed with Gamepad (`GamepadLeftStickX`) with an optional scale (`MouseX*0.01`).
+impl fmt::Display for AxisBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = match self {
+            AxisBinding::Buttons { negative, positive } => {
+                return write!(f, "{negative}/{positive}");
+            }
+            AxisBinding::MouseX { scale } => {
+                f.write_str("MouseX")?;
+                scale
+            }
+            AxisBinding::MouseY { scale } => {
+                f.write_str("MouseY")?;
+                scale
+            }
+            AxisBinding::ScrollX { scale } => {
+                f.write_str("ScrollX")?;
+                scale
+            }
+            AxisBinding::ScrollY { scale } => {
+                f.write_str("ScrollY")?;
+                scale
+            }
+            AxisBinding::Gamepad { axis, scale } => {
+                write!(f, "Gamepad{axis}")?;
+                scale
+            }
+        };
+
+        if *scale == 1.0 {
+            Ok(())
+        } else {
+            write!(f, "*{scale}")
+        }
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        if let Some((negative, positive)) = s.split_once('/') {
+            return Ok(AxisBinding::Buttons {
+                negative: negative.parse()?,
+                positive: positive.parse()?,
+            });
+        }
+
+        let (name, scale) = match s.split_once('*') {
+            Some((name, scale)) => {
+                let scale = scale
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|scale| scale.is_finite())
+                    .ok_or_else(|| anyhow!("invalid scale \"{scale}\" in \"{s}\""))?;
+                (name.trim(), scale)
+            }
+            None => (s, 1.0),
+        };
+
+        if let Some(axis) = strip_prefix_ignore_case(name, "Gamepad") {
+            return axis
+                .parse::<GamepadAxis>()
+                .map(|axis| AxisBinding::Gamepad { axis, scale })
+                .map_err(|_| anyhow!("unknown gamepad axis \"{name}\""));
+        }
+
+        match name.to_ascii_lowercase().as_str() {
+            "mousex" => Ok(AxisBinding::MouseX { scale }),
+            "mousey" => Ok(AxisBinding::MouseY { scale }),
+            "scrollx" => Ok(AxisBinding::ScrollX { scale }),
+            "scrolly" => Ok(AxisBinding::ScrollY { scale }),
+            _ => bail!(
+                "unknown axis \"{s}\", expected two bindings like \"A/D\", MouseX, MouseY, ScrollX, ScrollY or a gamepad axis"
+            ),
+        }
+    }
+}
+
+fn parse_key(name: &str) -> Result<Key> {
+    let name = name.trim();
+    name.parse::<Key>()
+        .map_err(|_| anyhow!("unknown key \"{name}\""))
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_round_trip_through_strings() {
+        for s in ["Ctrl+S", "MouseLeft", "GamepadSouth", "Space"] {
+            assert_eq!(s.parse::<Binding>().unwrap().to_string(), s);
+        }
+        for s in ["A/D", "MouseX*0.01", "GamepadLeftStickX", "ScrollY*-1"] {
+            assert_eq!(s.parse::<AxisBinding>().unwrap().to_string(), s);
+        }
+
+        // names are case insensitive, and written back out the way they're spelled in the enums
+        assert_eq!(
+            " ctrl + s ".parse::<Binding>().unwrap(),
+            Binding::chord([Key::Ctrl], Key::S)
+        );
+        assert_eq!(
+            "mouseright".parse::<Binding>().unwrap(),
+            Binding::Mouse(MouseBtn::Right)
+        );
+    }
+
+    #[test]
+    fn chord_modifiers_can_be_in_any_order() {
+        let a = "Ctrl+Shift+S".parse::<Binding>().unwrap();
+        let b = "Shift+Ctrl+S".parse::<Binding>().unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, "Ctrl+S".parse::<Binding>().unwrap());
+
+        let map = ActionMap::from_toml(
+            r#"
+            [actions]
+            save = ["Ctrl+Shift+S"]
+            save_as = ["Shift+Ctrl+S"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(map.conflicts().len(), 1);
+        assert_eq!(map.conflicts()[0].names, ["save", "save_as"]);
+    }
+
+    #[test]
+    fn scales_have_to_be_finite() {
+        for s in ["MouseX*NaN", "MouseX*inf", "MouseY*-inf", "MouseX*fast"] {
+            assert!(s.parse::<AxisBinding>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn every_unknown_name_is_reported() {
+        let error = ActionMap::from_toml(
+            r#"
+            [actions]
+            jump = ["Spcae", "MouseLeft"]
+
+            [axes]
+            move_x = ["A/Dee", "MouseZ"]
+            "#,
+        )
+        .unwrap_err()
+        .to_string();
+
+        for name in ["Spcae", "Dee", "MouseZ"] {
+            assert!(error.contains(name), "{name} is missing from: {error}");
+        }
+    }
+
+    #[test]
+    fn conflicts_include_axis_buttons() {
+        let mut map = ActionMap::new();
+        map.bind("jump", Key::Space);
+        map.bind("dash", Key::D);
+        map.bind_axis("move_x", AxisBinding::buttons(Key::A, Key::D));
+        map.bind_axis("move_y", AxisBinding::buttons(Key::S, Key::W));
+
+        assert_eq!(
+            map.conflicts(),
+            [BindingConflict {
+                binding: Binding::Key(Key::D),
+                names: vec!["dash".to_owned(), "move_x".to_owned()],
+            }]
+        );
+    }
+
+    #[test]
+    fn saved_bindings_load_back_the_same() {
+        let mut map = ActionMap::new();
+        map.bind("jump", Key::Space);
+        map.bind("jump", GamepadButton::South);
+        map.bind("save", Binding::chord([Key::Ctrl, Key::Shift], Key::S));
+        map.bind_axis("move_x", AxisBinding::buttons(Key::A, Key::D));
+        map.bind_axis("look_x", AxisBinding::MouseX { scale: 0.01 });
+
+        let path = std::env::temp_dir()
+            .join(format!("renoir_bindings_{}", std::process::id()))
+            .join("bindings.toml");
+        map.save(&path).unwrap();
+        let loaded = ActionMap::load(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        for action in ["jump", "save"] {
+            assert_eq!(loaded.bindings(action), map.bindings(action));
+        }
+        for axis in ["move_x", "look_x"] {
+            assert_eq!(loaded.axis_bindings(axis), map.axis_bindings(axis));
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use super::actions::InputSource;
+use super::{
+    ActionMap, Binding, GamepadAxis, GamepadButton, GamepadId, Key, KeyState, MouseBtn, RenoirInput,
+};
+
+/// Input contexts stop the same key press from doing two things at once, e.g. Escape closing the pause menu and
+/// pausing the game again. Contexts are stacked by priority, and a context can consume keys and mouse buttons for the
+/// rest of the frame so that every context below it sees them as released:
+/// ```
+/// # use renoir::prelude::*;
+/// let mut input = RenoirInput::new();
+/// input.push_context("gameplay", 0);
+/// input.push_context("pause_menu", 10);
+///
+/// input.press_key(Key::Escape);
+///
+/// // higher contexts go first
+/// if input.context("pause_menu").just_pressed(Key::Escape) {
+///     input.consume_key("pause_menu", Key::Escape);
+/// }
+///
+/// assert!(!input.context("gameplay").just_pressed(Key::Escape));
+/// // reading RenoirInput directly ignores contexts
+/// assert!(input.just_pressed(Key::Escape));
+/// ```
+/// Consuming only lasts until the end of the frame, so a context that wants a key to itself has to keep consuming
+/// it. A blocking context (see `set_context_blocking`) consumes everything, which suits menus that cover the game.
+pub struct InputContext<'a> {
+    input: &'a RenoirInput,
+    // every context above this one, which is whatever gets to consume input first
+    above: &'a [ContextState],
+}
+
+pub(crate) struct ContextState {
+    name: String,
+    priority: i32,
+    blocking: bool,
+    consumed_all: bool,
+    consumed_keys: Vec<Key>,
+    consumed_mouse_btns: Vec<MouseBtn>,
+}
+
+impl ContextState {
+    fn blocks_everything(&self) -> bool {
+        self.blocking || self.consumed_all
+    }
+
+    fn update(&mut self) {
+        self.consumed_all = false;
+        self.consumed_keys.clear();
+        self.consumed_mouse_btns.clear();
+    }
+}
+
+impl RenoirInput {
+    /// Adds a context to the stack, higher priorities get to read and consume input first. Contexts with the same
+    /// priority are stacked in the order they were pushed, and pushing a context that already exists moves it.
+    pub fn push_context(&mut self, name: impl Into<String>, priority: i32) {
+        let name = name.into();
+        self.remove_context(&name);
+
+        // the stack is kept sorted from the lowest priority up
+        let index = self
+            .contexts
+            .partition_point(|context| context.priority <= priority);
+        self.contexts.insert(
+            index,
+            ContextState {
+                name,
+                priority,
+                blocking: false,
+                consumed_all: false,
+                consumed_keys: Vec::new(),
+                consumed_mouse_btns: Vec::new(),
+            },
+        );
+    }
+
+    pub fn remove_context(&mut self, name: &str) {
+        self.contexts.retain(|context| context.name != name);
+    }
+
+    pub fn has_context(&self, name: &str) -> bool {
+        self.context_index(name).is_some()
+    }
+
+    /// The names of every context, from the highest priority down
+    pub fn contexts(&self) -> impl Iterator<Item = &str> {
+        self.contexts
+            .iter()
+            .rev()
+            .map(|context| context.name.as_str())
+    }
+
+    /// A blocking context hides all input from the contexts below it, every frame, without having to consume anything
+    pub fn set_context_blocking(&mut self, name: &str, blocking: bool) {
+        if let Some(context) = self.context_state_mut(name) {
+            context.blocking = blocking;
+        }
+    }
+
+    /// Input as a context sees it. A context that isn't on the stack sees input as if it were at the bottom.
+    pub fn context(&self, name: &str) -> InputContext<'_> {
+        let above = match self.context_index(name) {
+            Some(index) => &self.contexts[index + 1..],
+            None => &self.contexts[..],
+        };
+
+        InputContext { input: self, above }
+    }
+
+    /// Hides a key from every context below `context` for the rest of the frame
+    pub fn consume_key(&mut self, context: &str, key: Key) {
+        if let Some(context) = self.context_state_mut(context) {
+            context.consumed_keys.push(key);
+        }
+    }
+
+    /// Hides a mouse button from every context below `context` for the rest of the frame
+    pub fn consume_mouse_btn(&mut self, context: &str, btn: MouseBtn) {
+        if let Some(context) = self.context_state_mut(context) {
+            context.consumed_mouse_btns.push(btn);
+        }
+    }
+
+    /// Hides all input from every context below `context` for the rest of the frame
+    pub fn consume_all(&mut self, context: &str) {
+        if let Some(context) = self.context_state_mut(context) {
+            context.consumed_all = true;
+        }
+    }
+
+    // consuming only lasts a frame, called from RenoirInput::update
+    pub(crate) fn update_contexts(&mut self) {
+        self.contexts.iter_mut().for_each(ContextState::update);
+    }
+
+    fn context_index(&self, name: &str) -> Option<usize> {
+        self.contexts
+            .iter()
+            .position(|context| context.name == name)
+    }
+
+    fn context_state_mut(&mut self, name: &str) -> Option<&mut ContextState> {
+        let context = self
+            .contexts
+            .iter_mut()
+            .find(|context| context.name == name);
+        if context.is_none() {
+            log::warn!("there's no input context called \"{name}\"");
+        }
+        context
+    }
+}
+
+impl InputContext<'_> {
+    /// Whether a context above this one is hiding all input from it this frame
+    pub fn blocked(&self) -> bool {
+        self.above.iter().any(ContextState::blocks_everything)
+    }
+
+    pub fn get_key(&self, key: Key) -> KeyState {
+        let consumed = self
+            .above
+            .iter()
+            .any(|context| context.blocks_everything() || context.consumed_keys.contains(&key));
+
+        if consumed {
+            KeyState::Released
+        } else {
+            self.input.get_key(key)
+        }
+    }
+
+    pub fn pressed(&self, key: Key) -> bool {
+        self.get_key(key).pressed()
+    }
+
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.get_key(key).just_pressed()
+    }
+
+    pub fn released(&self, key: Key) -> bool {
+        self.get_key(key).released()
+    }
+
+    pub fn just_released(&self, key: Key) -> bool {
+        self.get_key(key).just_released()
+    }
+
+    pub fn get_mouse_btn(&self, btn: MouseBtn) -> KeyState {
+        let consumed = self.above.iter().any(|context| {
+            context.blocks_everything() || context.consumed_mouse_btns.contains(&btn)
+        });
+
+        if consumed {
+            KeyState::Released
+        } else {
+            self.input.get_mouse_btn(btn)
+        }
+    }
+
+    pub fn mouse_pressed(&self, btn: MouseBtn) -> bool {
+        self.get_mouse_btn(btn).pressed()
+    }
+
+    pub fn mouse_just_pressed(&self, btn: MouseBtn) -> bool {
+        self.get_mouse_btn(btn).just_pressed()
+    }
+
+    pub fn mouse_released(&self, btn: MouseBtn) -> bool {
+        self.get_mouse_btn(btn).released()
+    }
+
+    pub fn mouse_just_released(&self, btn: MouseBtn) -> bool {
+        self.get_mouse_btn(btn).just_released()
+    }
+
+    pub fn get_mouse_delta(&self) -> (f32, f32) {
+        if self.blocked() {
+            (0.0, 0.0)
+        } else {
+            self.input.get_mouse_delta()
+        }
+    }
+
+    pub fn get_scroll_delta(&self) -> (f32, f32) {
+        if self.blocked() {
+            (0.0, 0.0)
+        } else {
+            self.input.get_scroll_delta()
+        }
+    }
+
+    pub fn get_gamepad_btn(&self, id: GamepadId, button: GamepadButton) -> KeyState {
+        if self.blocked() {
+            KeyState::Released
+        } else {
+            self.input.get_gamepad_btn(id, button)
+        }
+    }
+
+    pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        if self.blocked() {
+            0.0
+        } else {
+            self.input.gamepad_axis(id, axis)
+        }
+    }
+
+    /// Like `RenoirInput::action`, with consumed bindings counting as released
+    pub fn action(&self, action: &str) -> KeyState {
+        self.action_state(action)
+    }
+
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.action(action).pressed()
+    }
+
+    pub fn action_just_pressed(&self, action: &str) -> bool {
+        self.action(action).just_pressed()
+    }
+
+    pub fn action_released(&self, action: &str) -> bool {
+        self.action(action).released()
+    }
+
+    pub fn action_just_released(&self, action: &str) -> bool {
+        self.action(action).just_released()
+    }
+
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axis_value(axis)
+    }
+
+    pub fn binding_state(&self, binding: &Binding) -> KeyState {
+        InputSource::binding_state(self, binding)
+    }
+}
+
+impl InputSource for InputContext<'_> {
+    fn actions(&self) -> &ActionMap {
+        &self.input.actions
+    }
+
+    fn key(&self, key: Key) -> KeyState {
+        self.get_key(key)
+    }
+
+    fn mouse_btn(&self, btn: MouseBtn) -> KeyState {
+        self.get_mouse_btn(btn)
+    }
+
+    fn gamepad_btn(&self, button: GamepadButton) -> KeyState {
+        if self.blocked() {
+            KeyState::Released
+        } else {
+            self.input.any_gamepad_btn(button)
+        }
+    }
+
+    fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        if self.blocked() {
+            0.0
+        } else {
+            self.input.any_gamepad_axis(axis)
+        }
+    }
+
+    fn mouse_delta(&self) -> (f32, f32) {
+        self.get_mouse_delta()
+    }
+
+    fn scroll_delta(&self) -> (f32, f32) {
+        self.get_scroll_delta()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::input::AxisBinding;
+
+    fn menu_over_gameplay() -> RenoirInput {
+        let mut input = RenoirInput::new();
+        input.push_context("gameplay", 0);
+        input.push_context("menu", 10);
+        input
+    }
+
+    #[test]
+    fn blocking_contexts_hide_input_from_the_ones_below() {
+        let mut input = menu_over_gameplay();
+        input.set_context_blocking("menu", true);
+        input.press_key(Key::W);
+        input.press_mouse_btn(MouseBtn::Left);
+        input.move_mouse(3.0, 4.0);
+
+        let gameplay = input.context("gameplay");
+        assert!(gameplay.blocked());
+        assert!(!gameplay.pressed(Key::W));
+        assert!(!gameplay.mouse_pressed(MouseBtn::Left));
+        assert_eq!(gameplay.get_mouse_delta(), (0.0, 0.0));
+
+        // the blocking context itself still sees everything, and blocking lasts across frames
+        assert!(input.context("menu").just_pressed(Key::W));
+        input.update();
+        assert!(!input.context("gameplay").pressed(Key::W));
+
+        input.set_context_blocking("menu", false);
+        assert!(input.context("gameplay").pressed(Key::W));
+    }
+
+    #[test]
+    fn consuming_only_lasts_until_the_next_update() {
+        let mut input = menu_over_gameplay();
+        input.press_mouse_btn(MouseBtn::Left);
+        input.press_key(Key::Escape);
+
+        input.consume_mouse_btn("menu", MouseBtn::Left);
+        let gameplay = input.context("gameplay");
+        assert!(!gameplay.mouse_just_pressed(MouseBtn::Left));
+        assert!(gameplay.just_pressed(Key::Escape));
+        assert!(!gameplay.blocked());
+
+        input.consume_all("menu");
+        let gameplay = input.context("gameplay");
+        assert!(gameplay.blocked());
+        assert!(!gameplay.pressed(Key::Escape));
+
+        input.update();
+        let gameplay = input.context("gameplay");
+        assert!(!gameplay.blocked());
+        assert!(gameplay.pressed(Key::Escape));
+        assert!(gameplay.mouse_pressed(MouseBtn::Left));
+    }
+
+    #[test]
+    fn actions_and_chords_read_consumed_keys_as_released() {
+        let mut input = menu_over_gameplay();
+        input.actions.bind("jump", Key::Space);
+        input
+            .actions
+            .bind("save", Binding::chord([Key::Ctrl], Key::S));
+        input
+            .actions
+            .bind_axis("move_x", AxisBinding::buttons(Key::A, Key::D));
+
+        input.press_key(Key::Space);
+        input.press_key(Key::Ctrl);
+        input.press_key(Key::S);
+        input.press_key(Key::D);
+        input.consume_key("menu", Key::Space);
+        input.consume_key("menu", Key::Ctrl);
+        input.consume_key("menu", Key::D);
+
+        let gameplay = input.context("gameplay");
+        assert!(gameplay.action_released("jump"));
+        assert!(gameplay.action_released("save"));
+        assert_eq!(gameplay.axis("move_x"), 0.0);
+
+        let menu = input.context("menu");
+        assert!(menu.action_just_pressed("jump"));
+        assert!(menu.action_just_pressed("save"));
+        assert_eq!(menu.axis("move_x"), 1.0);
+    }
+
+    #[test]
+    fn equal_priorities_stack_in_the_order_they_were_pushed() {
+        let mut input = RenoirInput::new();
+        input.push_context("first", 5);
+        input.push_context("second", 5);
+        input.push_context("low", 0);
+        assert_eq!(
+            input.contexts().collect::<Vec<_>>(),
+            ["second", "first", "low"]
+        );
+
+        // pushing again moves a context to the top of its priority
+        input.push_context("first", 5);
+        assert_eq!(
+            input.contexts().collect::<Vec<_>>(),
+            ["first", "second", "low"]
+        );
+
+        input.press_key(Key::E);
+        input.consume_key("first", Key::E);
+        assert!(!input.context("second").pressed(Key::E));
+        assert!(input.context("first").pressed(Key::E));
+    }
+
+    #[test]
+    fn unknown_contexts_are_at_the_bottom() {
+        let mut input = menu_over_gameplay();
+        input.press_key(Key::Q);
+        input.consume_key("gameplay", Key::Q);
+
+        assert!(!input.has_context("missing"));
+        assert!(!input.context("missing").pressed(Key::Q));
+
+        // consuming for a context that doesn't exist does nothing
+        input.press_key(Key::E);
+        input.consume_key("missing", Key::E);
+        input.consume_all("missing");
+        assert!(input.context("gameplay").pressed(Key::E));
+        assert!(input.context("missing").pressed(Key::E));
+    }
+}
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+
+use super::{GamepadEvent, Key, KeyState, LogicalKey, MouseBtn, RenoirInput};
+
+/// A single change to the input state. Every window, device and gamepad event RenoirInput handles is turned into one
+/// of these first, which is what makes recording and replaying input possible.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key {
+        key: Key,
+        pressed: bool,
+    },
+    /// What a key press means in the keyboard layout, sent along with the Key event for the physical key (see
+    /// LogicalKey)
+    LogicalKey {
+        key: LogicalKey,
+        physical: Option<Key>,
+        pressed: bool,
+    },
+    /// The OS repeating a held key, which doesn't change its KeyState
+    KeyRepeat(Key),
+    /// Typed text, following the keyboard layout
+    Text(String),
+    Mouse {
+        button: MouseBtn,
+        pressed: bool,
+    },
+    /// The cursor's position in the window, in physical pixels
+    CursorMoved {
+        x: f32,
+        y: f32,
+    },
+    CursorEntered,
+    CursorLeft,
+    /// Raw mouse movement, which keeps going when the cursor is grabbed or at the edge of the screen
+    MouseMotion {
+        dx: f32,
+        dy: f32,
+    },
+    Scroll {
+        dx: f32,
+        dy: f32,
+    },
+    ImeEnabled,
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    ImeCommit(String),
+    ImeDisabled,
+    Gamepad(GamepadEvent),
+}
+
+/// Simulating input, mostly for testing gameplay code without a window. Events take effect immediately, and `update`
+/// moves on to the next frame like RenoirApp does after the main function:
+/// ```
+/// # use renoir::prelude::*;
+/// let mut input = RenoirInput::new();
+/// input.actions.bind("jump", Key::Space);
+///
+/// input.press_key(Key::Space);
+/// assert!(input.action_just_pressed("jump"));
+///
+/// input.update();
+/// assert_eq!(input.action("jump"), KeyState::Pressed);
+///
+/// input.release_key(Key::Space);
+/// assert!(input.action_just_released("jump"));
+/// ```
+impl RenoirInput {
+    /// Applies an event as if it came from the window, it's also recorded if input is being recorded
+    pub fn inject(&mut self, event: InputEvent) {
+        self.apply(event);
+    }
+
+    pub fn press_key(&mut self, key: Key) {
+        self.apply(InputEvent::Key { key, pressed: true });
+    }
+
+    pub fn release_key(&mut self, key: Key) {
+        self.apply(InputEvent::Key {
+            key,
+            pressed: false,
+        });
+    }
+
+    pub fn press_mouse_btn(&mut self, button: MouseBtn) {
+        self.apply(InputEvent::Mouse {
+            button,
+            pressed: true,
+        });
+    }
+
+    pub fn release_mouse_btn(&mut self, button: MouseBtn) {
+        self.apply(InputEvent::Mouse {
+            button,
+            pressed: false,
+        });
+    }
+
+    /// Moves the cursor to a position in the window, in physical pixels
+    pub fn move_cursor_to(&mut self, x: f32, y: f32) {
+        self.apply(InputEvent::CursorMoved { x, y });
+    }
+
+    /// Moves the mouse by a raw amount, which is what `get_mouse_delta` and mouse axes read
+    pub fn move_mouse(&mut self, dx: f32, dy: f32) {
+        self.apply(InputEvent::MouseMotion { dx, dy });
+    }
+
+    pub fn scroll(&mut self, dx: f32, dy: f32) {
+        self.apply(InputEvent::Scroll { dx, dy });
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        self.apply(InputEvent::Text(text.to_owned()));
+    }
+
+    // every change to the input state goes through here
+    pub(crate) fn apply(&mut self, event: InputEvent) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.events.push(event.clone());
+        }
+
+        match event {
+            InputEvent::Key { key, pressed } => {
+                let state = KeyState::from_pressed(pressed);
+                self.keys[key as usize] = state;
+                self.key_stream.push((key, state));
+            }
+            InputEvent::LogicalKey {
+                key,
+                physical,
+                pressed,
+            } => self.process_logical_key(key, physical, pressed),
+            InputEvent::KeyRepeat(key) => self.repeated_keys.push(key),
+            InputEvent::Text(text) => self.push_text(&text),
+            InputEvent::Mouse { button, pressed } => {
+                self.mouse.buttons[button as usize] = KeyState::from_pressed(pressed);
+            }
+            InputEvent::CursorMoved { x, y } => {
+                self.mouse.cursor_position = Some((x, y));
+                // some platforms don't send CursorEntered before the first move
+                self.mouse.cursor_inside = true;
+            }
+            InputEvent::CursorEntered => self.mouse.cursor_inside = true,
+            InputEvent::CursorLeft => self.mouse.cursor_inside = false,
+            // winit can send several motion and scroll events per frame, so these add up until the next update
+            InputEvent::MouseMotion { dx, dy } => {
+                self.mouse.cursor_delta.0 += dx;
+                self.mouse.cursor_delta.1 += dy;
+            }
+            InputEvent::Scroll { dx, dy } => {
+                self.mouse.scroll_delta.0 += dx;
+                self.mouse.scroll_delta.1 += dy;
+                self.mouse.scroll_total.0 += dx;
+                self.mouse.scroll_total.1 += dy;
+            }
+            InputEvent::ImeEnabled => self.ime_active = true,
+            InputEvent::ImePreedit { text, cursor } => {
+                self.ime_preedit = text;
+                self.ime_preedit_cursor = cursor;
+            }
+            InputEvent::ImeCommit(text) => {
+                self.ime_preedit.clear();
+                self.ime_preedit_cursor = None;
+                self.push_text(&text);
+            }
+            InputEvent::ImeDisabled => {
+                self.ime_active = false;
+                self.ime_preedit.clear();
+                self.ime_preedit_cursor = None;
+            }
+            InputEvent::Gamepad(event) => self.process_gamepad_event(event),
+        }
+    }
+
+    // control characters (backspace, enter, etc.) are left out, those are easier to handle as keys
+    fn push_text(&mut self, text: &str) {
+        self.text.extend(text.chars().filter(|c| !c.is_control()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_frame_values_reset_on_update() {
+        let mut input = RenoirInput::new();
+
+        input.move_mouse(2.0, 1.0);
+        input.move_mouse(1.0, -3.0);
+        input.scroll(0.0, 1.0);
+        input.type_text("a\u{8}b");
+        input.inject(InputEvent::KeyRepeat(Key::Backspace));
+        assert_eq!(input.get_mouse_delta(), (3.0, -2.0));
+        assert_eq!(input.get_scroll_delta(), (0.0, 1.0));
+        // control characters are left out of typed text
+        assert_eq!(input.text_input(), "ab");
+        assert!(input.repeated(Key::Backspace));
+
+        input.update();
+        assert_eq!(input.get_mouse_delta(), (0.0, 0.0));
+        assert_eq!(input.get_scroll_delta(), (0.0, 0.0));
+        assert_eq!(input.get_scroll_total(), (0.0, 1.0));
+        assert_eq!(input.text_input(), "");
+        assert!(!input.repeated(Key::Backspace));
+    }
+
+    #[test]
+    fn cursor_moves_and_leaves() {
+        let mut input = RenoirInput::new();
+        assert_eq!(input.get_cursor_position(), None);
+
+        input.move_cursor_to(10.0, 20.0);
+        assert_eq!(input.get_cursor_position(), Some((10.0, 20.0)));
+        assert!(input.cursor_in_window());
+
+        input.inject(InputEvent::CursorLeft);
+        assert!(!input.cursor_in_window());
+    }
+
+    #[test]
+    fn ime_composition_commits_as_text() {
+        let mut input = RenoirInput::new();
+
+        input.inject(InputEvent::ImeEnabled);
+        input.inject(InputEvent::ImePreedit {
+            text: "ni".to_owned(),
+            cursor: Some((2, 2)),
+        });
+        assert!(input.ime_active());
+        assert_eq!(input.ime_preedit(), "ni");
+        assert_eq!(input.text_input(), "");
+
+        input.inject(InputEvent::ImeCommit("你".to_owned()));
+        assert_eq!(input.ime_preedit(), "");
+        assert_eq!(input.ime_preedit_cursor(), None);
+        assert_eq!(input.text_input(), "你");
+    }
+
+    #[test]
+    fn injected_events_are_recorded() {
+        let mut input = RenoirInput::new();
+        input.start_recording();
+
+        input.press_key(Key::Space);
+        input.release_key(Key::Space);
+        input.record_frame(0.016);
+
+        let recording = input.stop_recording().unwrap();
+        assert_eq!(
+            recording.frames()[0].events,
+            vec![
+                InputEvent::Key {
+                    key: Key::Space,
+                    pressed: true
+                },
+                InputEvent::Key {
+                    key: Key::Space,
+                    pressed: false
+                },
+            ]
+        );
+    }
+}
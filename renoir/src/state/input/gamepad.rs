@@ -0,0 +1,598 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+
+use super::{InputEvent, KeyState, RenoirInput};
+
+/// Buttons are named by their position rather than their label, since that differs between controllers (South is
+/// A on an Xbox controller and Cross on a PlayStation one).
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    strum::EnumCount,
+    strum::FromRepr,
+    strum::Display,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Sticks go from -1.0 to 1.0 (with up being positive), triggers go from 0.0 to 1.0
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    strum::EnumCount,
+    strum::FromRepr,
+    strum::Display,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct GamepadId(pub u32);
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+    Button {
+        id: GamepadId,
+        button: GamepadButton,
+        pressed: bool,
+    },
+    Axis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+/// Where gamepad events come from. RenoirInput polls every backend it has once per frame, before the main function
+/// runs. Real controllers are read by `GilrsBackend` when the "gilrs" feature is enabled, which RenoirApp adds by
+/// itself.
+pub trait GamepadBackend {
+    /// Returns every event since the last poll, in the order they happened
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// An in-memory gamepad for tests and scripted input. Clones share the same pad, so keep one around to press buttons
+/// on after handing a clone to RenoirInput:
+/// ```
+/// # use renoir::prelude::*;
+/// let pad = VirtualGamepad::new(GamepadId(0));
+/// let mut input = RenoirInput::new();
+/// input.add_gamepad_backend(pad.clone());
+///
+/// pad.connect();
+/// pad.press(GamepadButton::South);
+/// pad.set_axis(GamepadAxis::LeftStickX, 0.5);
+/// input.poll_gamepads();
+///
+/// assert!(input.gamepad_just_pressed(GamepadId(0), GamepadButton::South));
+/// assert!(input.gamepad_axis(GamepadId(0), GamepadAxis::LeftStickX) > 0.0);
+/// ```
+#[derive(Clone)]
+pub struct VirtualGamepad {
+    id: GamepadId,
+    events: Arc<Mutex<Vec<GamepadEvent>>>,
+}
+
+impl VirtualGamepad {
+    pub fn new(id: GamepadId) -> Self {
+        Self {
+            id,
+            events: Arc::default(),
+        }
+    }
+
+    pub fn id(&self) -> GamepadId {
+        self.id
+    }
+
+    pub fn connect(&self) {
+        self.push(GamepadEvent::Connected(self.id));
+    }
+
+    pub fn disconnect(&self) {
+        self.push(GamepadEvent::Disconnected(self.id));
+    }
+
+    pub fn press(&self, button: GamepadButton) {
+        self.push(GamepadEvent::Button {
+            id: self.id,
+            button,
+            pressed: true,
+        });
+    }
+
+    pub fn release(&self, button: GamepadButton) {
+        self.push(GamepadEvent::Button {
+            id: self.id,
+            button,
+            pressed: false,
+        });
+    }
+
+    pub fn set_axis(&self, axis: GamepadAxis, value: f32) {
+        self.push(GamepadEvent::Axis {
+            id: self.id,
+            axis,
+            value,
+        });
+    }
+
+    fn push(&self, event: GamepadEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl GamepadBackend for VirtualGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+/// Reads real controllers through the gilrs crate. RenoirApp adds one of these when the "gilrs" feature is enabled,
+/// so it only needs creating when using RenoirInput on its own.
+#[cfg(feature = "gilrs")]
+pub struct GilrsBackend {
+    gilrs: gilrs::Gilrs,
+    // pads that were plugged in before the backend was created don't get a Connected event from gilrs
+    announced: bool,
+}
+
+#[cfg(feature = "gilrs")]
+impl GilrsBackend {
+    pub fn new() -> anyhow::Result<Self> {
+        let gilrs = gilrs::Gilrs::new()
+            .map_err(|e| anyhow::anyhow!("failed to start reading gamepads: {e}"))?;
+        Ok(Self {
+            gilrs,
+            announced: false,
+        })
+    }
+}
+
+#[cfg(feature = "gilrs")]
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+
+        if !self.announced {
+            self.announced = true;
+            events.extend(
+                self.gilrs
+                    .gamepads()
+                    .map(|(id, _)| GamepadEvent::Connected(gilrs_id(id))),
+            );
+        }
+
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let id = gilrs_id(id);
+            let event = match event {
+                gilrs::EventType::Connected => Some(GamepadEvent::Connected(id)),
+                gilrs::EventType::Disconnected => Some(GamepadEvent::Disconnected(id)),
+                gilrs::EventType::ButtonPressed(button, _) => {
+                    gilrs_button(button).map(|button| GamepadEvent::Button {
+                        id,
+                        button,
+                        pressed: true,
+                    })
+                }
+                gilrs::EventType::ButtonReleased(button, _) => {
+                    gilrs_button(button).map(|button| GamepadEvent::Button {
+                        id,
+                        button,
+                        pressed: false,
+                    })
+                }
+                // gilrs reports the analog triggers as buttons with a value
+                gilrs::EventType::ButtonChanged(button, value, _) => {
+                    gilrs_trigger(button).map(|axis| GamepadEvent::Axis { id, axis, value })
+                }
+                gilrs::EventType::AxisChanged(axis, value, _) => {
+                    gilrs_axis(axis).map(|axis| GamepadEvent::Axis { id, axis, value })
+                }
+                _ => None,
+            };
+            events.extend(event);
+        }
+
+        events
+    }
+}
+
+#[cfg(feature = "gilrs")]
+fn gilrs_id(id: gilrs::GamepadId) -> GamepadId {
+    GamepadId(usize::from(id) as u32)
+}
+
+// gilrs calls the bumpers triggers, and the triggers second triggers
+#[cfg(feature = "gilrs")]
+fn gilrs_button(button: gilrs::Button) -> Option<GamepadButton> {
+    use gilrs::Button;
+
+    Some(match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::LeftTrigger => GamepadButton::LeftBumper,
+        Button::RightTrigger => GamepadButton::RightBumper,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftStick,
+        Button::RightThumb => GamepadButton::RightStick,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "gilrs")]
+fn gilrs_trigger(button: gilrs::Button) -> Option<GamepadAxis> {
+    match button {
+        gilrs::Button::LeftTrigger2 => Some(GamepadAxis::LeftTrigger),
+        gilrs::Button::RightTrigger2 => Some(GamepadAxis::RightTrigger),
+        _ => None,
+    }
+}
+
+// like Renoir, gilrs already has up as positive on the sticks
+#[cfg(feature = "gilrs")]
+fn gilrs_axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+    match axis {
+        gilrs::Axis::LeftStickX => Some(GamepadAxis::LeftStickX),
+        gilrs::Axis::LeftStickY => Some(GamepadAxis::LeftStickY),
+        gilrs::Axis::RightStickX => Some(GamepadAxis::RightStickX),
+        gilrs::Axis::RightStickY => Some(GamepadAxis::RightStickY),
+        _ => None,
+    }
+}
+
+pub(crate) struct GamepadState {
+    id: GamepadId,
+    connected: bool,
+    buttons: [KeyState; GamepadButton::COUNT],
+    // raw values, the deadzone is applied when they're read
+    axes: [f32; GamepadAxis::COUNT],
+}
+
+impl GamepadState {
+    fn new(id: GamepadId) -> Self {
+        Self {
+            id,
+            connected: true,
+            buttons: [KeyState::Released; GamepadButton::COUNT],
+            axes: [0.0; GamepadAxis::COUNT],
+        }
+    }
+
+    fn update(&mut self) {
+        self.buttons.iter_mut().for_each(|btn| {
+            *btn = btn.update();
+        });
+    }
+}
+
+impl RenoirInput {
+    /// Adds a source of gamepad events, there can be any number of these (e.g. a hardware backend and a virtual pad)
+    pub fn add_gamepad_backend(&mut self, backend: impl GamepadBackend + 'static) {
+        self.gamepad_backends.push(Box::new(backend));
+    }
+
+    /// Reads new events from every gamepad backend. RenoirApp calls this every frame before the main function, so
+    /// it only needs calling when using RenoirInput on its own.
+    pub fn poll_gamepads(&mut self) {
+        let events = self
+            .gamepad_backends
+            .iter_mut()
+            .flat_map(|backend| backend.poll())
+            .collect::<Vec<_>>();
+
+        for event in events {
+            self.apply(InputEvent::Gamepad(event));
+        }
+    }
+
+    // forgets pads that disconnected last frame and advances the button states, called from RenoirInput::update
+    pub(crate) fn update_gamepads(&mut self) {
+        self.gamepads.retain(|pad| pad.connected);
+        self.gamepads.iter_mut().for_each(GamepadState::update);
+        self.gamepad_connections.clear();
+    }
+
+    pub(crate) fn process_gamepad_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(id) => {
+                match self.gamepad_mut(id) {
+                    Some(pad) => pad.connected = true,
+                    None => self.gamepads.push(GamepadState::new(id)),
+                }
+                self.gamepad_connections.push(event);
+            }
+            GamepadEvent::Disconnected(id) => {
+                // the pad sticks around until the next update so that held buttons show up as just released
+                if let Some(pad) = self.gamepad_mut(id) {
+                    pad.connected = false;
+                    pad.axes = [0.0; GamepadAxis::COUNT];
+                    pad.buttons.iter_mut().for_each(|btn| {
+                        if btn.pressed() {
+                            *btn = KeyState::JustReleased;
+                        }
+                    });
+                }
+                self.gamepad_connections.push(event);
+            }
+            GamepadEvent::Button {
+                id,
+                button,
+                pressed,
+            } => {
+                if let Some(pad) = self.gamepad_mut(id) {
+                    pad.buttons[button as usize] = KeyState::from_pressed(pressed);
+                }
+            }
+            GamepadEvent::Axis { id, axis, value } => {
+                if let Some(pad) = self.gamepad_mut(id) {
+                    // a broken driver (or recording) shouldn't be able to turn every axis binding into NaN
+                    pad.axes[axis as usize] = if value.is_finite() {
+                        value.clamp(-1.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                }
+            }
+        }
+    }
+
+    // events that let go of every held button and stick on every pad, used when a recording stops driving them
+    pub(super) fn gamepad_release_events(&self) -> Vec<GamepadEvent> {
+        self.gamepads
+            .iter()
+            .flat_map(|pad| {
+                let buttons = (0..GamepadButton::COUNT)
+                    .filter_map(GamepadButton::from_repr)
+                    .filter(|&button| pad.buttons[button as usize].pressed())
+                    .map(|button| GamepadEvent::Button {
+                        id: pad.id,
+                        button,
+                        pressed: false,
+                    });
+                let axes = (0..GamepadAxis::COUNT)
+                    .filter_map(GamepadAxis::from_repr)
+                    .filter(|&axis| pad.axes[axis as usize] != 0.0)
+                    .map(|axis| GamepadEvent::Axis {
+                        id: pad.id,
+                        axis,
+                        value: 0.0,
+                    });
+                buttons.chain(axes)
+            })
+            .collect()
+    }
+
+    fn gamepad(&self, id: GamepadId) -> Option<&GamepadState> {
+        self.gamepads.iter().find(|pad| pad.id == id)
+    }
+
+    fn gamepad_mut(&mut self, id: GamepadId) -> Option<&mut GamepadState> {
+        self.gamepads.iter_mut().find(|pad| pad.id == id)
+    }
+
+    /// Every connected gamepad, in the order they were connected
+    pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads
+            .iter()
+            .filter(|pad| pad.connected)
+            .map(|pad| pad.id)
+    }
+
+    pub fn gamepad_connected(&self, id: GamepadId) -> bool {
+        self.gamepad(id).is_some_and(|pad| pad.connected)
+    }
+
+    /// The Connected and Disconnected events from this frame
+    pub fn gamepad_connections(&self) -> &[GamepadEvent] {
+        &self.gamepad_connections
+    }
+
+    /// How far a stick or trigger has to move before it registers, from 0.0 to 1.0 (0.15 by default)
+    pub fn gamepad_deadzone(&self) -> f32 {
+        self.gamepad_deadzone
+    }
+
+    /// Sets the deadzone, clamped to 0.0 to 0.99. NaN is ignored.
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        if deadzone.is_nan() {
+            log::warn!("ignoring invalid gamepad deadzone {deadzone}");
+            return;
+        }
+        self.gamepad_deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
+    pub fn get_gamepad_btn(&self, id: GamepadId, button: GamepadButton) -> KeyState {
+        self.gamepad(id)
+            .map_or(KeyState::Released, |pad| pad.buttons[button as usize])
+    }
+
+    pub fn gamepad_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.get_gamepad_btn(id, button).pressed()
+    }
+
+    pub fn gamepad_just_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.get_gamepad_btn(id, button).just_pressed()
+    }
+
+    pub fn gamepad_released(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.get_gamepad_btn(id, button).released()
+    }
+
+    pub fn gamepad_just_released(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.get_gamepad_btn(id, button).just_released()
+    }
+
+    /// An axis with the deadzone applied. Values inside the deadzone are 0.0 and the rest is rescaled so that the
+    /// axis still smoothly covers the whole range.
+    pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        let value = self.gamepad(id).map_or(0.0, |pad| pad.axes[axis as usize]);
+        apply_deadzone(value, self.gamepad_deadzone)
+    }
+
+    // the combined state of a button across every pad, for bindings
+    pub(crate) fn any_gamepad_btn(&self, button: GamepadButton) -> KeyState {
+        super::actions::combine(self.gamepads.iter().map(|pad| pad.buttons[button as usize]))
+    }
+
+    // the sum of an axis across every pad, for bindings
+    pub(crate) fn any_gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepads
+            .iter()
+            .map(|pad| apply_deadzone(pad.axes[axis as usize], self.gamepad_deadzone))
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+}
+
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value.abs() <= deadzone {
+        return 0.0;
+    }
+    value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn deadzone_is_cut_out_and_the_rest_rescaled() {
+        assert_eq!(apply_deadzone(0.1, 0.2), 0.0);
+        assert_eq!(apply_deadzone(-0.2, 0.2), 0.0);
+        assert!(close(apply_deadzone(0.6, 0.2), 0.5));
+        assert!(close(apply_deadzone(-0.6, 0.2), -0.5));
+        assert!(close(apply_deadzone(1.0, 0.2), 1.0));
+        assert!(close(apply_deadzone(-1.0, 0.2), -1.0));
+        assert_eq!(apply_deadzone(0.3, 0.0), 0.3);
+    }
+
+    #[test]
+    fn virtual_pad_buttons_axes_and_connections() {
+        let pad = VirtualGamepad::new(GamepadId(3));
+        let mut input = RenoirInput::new();
+        input.add_gamepad_backend(pad.clone());
+        input.set_gamepad_deadzone(0.2);
+
+        // events for a pad that isn't connected are ignored
+        pad.press(GamepadButton::North);
+        pad.connect();
+        pad.press(GamepadButton::South);
+        pad.set_axis(GamepadAxis::LeftStickY, 0.6);
+        pad.set_axis(GamepadAxis::RightStickX, 0.1);
+        input.poll_gamepads();
+
+        let id = pad.id();
+        assert_eq!(input.gamepads().collect::<Vec<_>>(), vec![id]);
+        assert_eq!(input.gamepad_connections(), &[GamepadEvent::Connected(id)]);
+        assert!(input
+            .get_gamepad_btn(id, GamepadButton::South)
+            .just_pressed());
+        assert!(input.get_gamepad_btn(id, GamepadButton::North).released());
+        assert!(close(input.gamepad_axis(id, GamepadAxis::LeftStickY), 0.5));
+        assert_eq!(input.gamepad_axis(id, GamepadAxis::RightStickX), 0.0);
+
+        input.update();
+        assert!(input.gamepad_connections().is_empty());
+        assert_eq!(
+            input.get_gamepad_btn(id, GamepadButton::South),
+            KeyState::Pressed
+        );
+
+        // held buttons are released when the pad goes away, and the pad is forgotten a frame later
+        pad.disconnect();
+        input.poll_gamepads();
+        assert!(!input.gamepad_connected(id));
+        assert!(input
+            .get_gamepad_btn(id, GamepadButton::South)
+            .just_released());
+        assert_eq!(input.gamepad_axis(id, GamepadAxis::LeftStickY), 0.0);
+        input.update();
+        assert_eq!(input.gamepads().count(), 0);
+    }
+
+    #[test]
+    fn non_finite_axis_values_are_zero() {
+        let pad = VirtualGamepad::new(GamepadId(0));
+        let mut input = RenoirInput::new();
+        input.add_gamepad_backend(pad.clone());
+        input.actions.bind_axis(
+            "move_x",
+            crate::state::input::AxisBinding::Gamepad {
+                axis: GamepadAxis::LeftStickX,
+                scale: 1.0,
+            },
+        );
+
+        pad.connect();
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            pad.set_axis(GamepadAxis::LeftStickX, value);
+            input.poll_gamepads();
+            assert_eq!(
+                input.gamepad_axis(GamepadId(0), GamepadAxis::LeftStickX),
+                0.0
+            );
+            assert_eq!(input.axis("move_x"), 0.0);
+        }
+
+        input.set_gamepad_deadzone(f32::NAN);
+        assert!(input.gamepad_deadzone().is_finite());
+    }
+}
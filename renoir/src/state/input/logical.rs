@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Key, KeyState, RenoirInput};
+
+/// A key by what it means in the keyboard layout rather than where it is. `Key` is the physical key, so `Key::Q` is
+/// always the top left letter, even on an AZERTY keyboard where it types an A. `LogicalKey::character('a')` is
+/// whichever key types an A.
+///
+/// Characters are stored lowercase and without modifiers wherever the platform allows it, so Shift+A is still 'a'.
+/// Keys that don't type anything are named after winit's `NamedKey` (e.g. "Enter", "ArrowLeft", "Shift"):
+/// ```
+/// # use renoir::prelude::*;
+/// let mut input = RenoirInput::new();
+///
+/// // what RenoirApp sends when Q is pressed on an AZERTY keyboard
+/// input.press_key(Key::Q);
+/// input.inject(InputEvent::LogicalKey {
+///     key: LogicalKey::character('a'),
+///     physical: Some(Key::Q),
+///     pressed: true,
+/// });
+///
+/// assert!(input.char_just_pressed('a'));
+/// assert!(input.pressed(Key::Q));
+/// assert_eq!(input.key_display_name(Key::Q).as_deref(), Some("A"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum LogicalKey {
+    Character(String),
+    Named(String),
+}
+
+impl LogicalKey {
+    pub fn character(c: impl Into<String>) -> Self {
+        Self::Character(c.into().to_lowercase())
+    }
+
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::Named(name.into())
+    }
+
+    pub(crate) fn from_winit(key: &winit::keyboard::Key) -> Option<Self> {
+        use winit::keyboard::Key as WinitKey;
+
+        match key {
+            WinitKey::Character(c) => Some(Self::character(c.as_str())),
+            // NamedKey's variant names are what the web and most docs call these keys anyway
+            WinitKey::Named(named) => Some(Self::named(format!("{named:?}"))),
+            WinitKey::Dead(Some(c)) => Some(Self::character(*c)),
+            WinitKey::Dead(None) | WinitKey::Unidentified(_) => None,
+        }
+    }
+}
+
+impl From<char> for LogicalKey {
+    fn from(c: char) -> Self {
+        Self::character(c)
+    }
+}
+
+/// Characters are shown uppercase, like they are on keycaps
+impl fmt::Display for LogicalKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogicalKey::Character(c) => write!(f, "{}", c.to_uppercase()),
+            LogicalKey::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LogicalKeys {
+    states: HashMap<LogicalKey, KeyState>,
+    // what each held physical key produced when it went down, so that it's released properly even if the modifiers
+    // changed in the meantime (Shift+1 going down as '!' and coming back up as '1')
+    held: HashMap<Key, LogicalKey>,
+    // what each physical key last produced, which is all of the layout that winit tells us about
+    layout: HashMap<Key, LogicalKey>,
+}
+
+impl LogicalKeys {
+    pub(crate) fn update(&mut self) {
+        self.states.values_mut().for_each(|state| {
+            *state = state.update();
+        });
+        self.states.retain(|_, state| *state != KeyState::Released);
+    }
+}
+
+impl RenoirInput {
+    pub(crate) fn process_logical_key(
+        &mut self,
+        key: LogicalKey,
+        physical: Option<Key>,
+        pressed: bool,
+    ) {
+        let logical = &mut self.logical;
+        let key = match (physical, pressed) {
+            (Some(physical), true) => {
+                logical.held.insert(physical, key.clone());
+                logical.layout.insert(physical, key.clone());
+                key
+            }
+            (Some(physical), false) => logical.held.remove(&physical).unwrap_or(key),
+            (None, _) => key,
+        };
+
+        // e.g. both Enter keys are "Enter", which stays down until both are let go
+        if !pressed && logical.held.values().any(|held| *held == key) {
+            return;
+        }
+
+        logical.states.insert(key, KeyState::from_pressed(pressed));
+    }
+
+    // every held logical key along with the physical key holding it down
+    pub(super) fn held_logical_keys(&self) -> Vec<(Key, LogicalKey)> {
+        self.logical
+            .held
+            .iter()
+            .map(|(physical, key)| (*physical, key.clone()))
+            .collect()
+    }
+
+    pub fn get_logical_key(&self, key: &LogicalKey) -> KeyState {
+        self.logical.states.get(key).copied().unwrap_or_default()
+    }
+
+    pub fn logical_pressed(&self, key: &LogicalKey) -> bool {
+        self.get_logical_key(key).pressed()
+    }
+
+    pub fn logical_just_pressed(&self, key: &LogicalKey) -> bool {
+        self.get_logical_key(key).just_pressed()
+    }
+
+    pub fn logical_released(&self, key: &LogicalKey) -> bool {
+        self.get_logical_key(key).released()
+    }
+
+    pub fn logical_just_released(&self, key: &LogicalKey) -> bool {
+        self.get_logical_key(key).just_released()
+    }
+
+    /// Whether the key that types `c` in the current layout is held, ignoring case
+    pub fn char_pressed(&self, c: char) -> bool {
+        self.logical_pressed(&LogicalKey::character(c))
+    }
+
+    pub fn char_just_pressed(&self, c: char) -> bool {
+        self.logical_just_pressed(&LogicalKey::character(c))
+    }
+
+    pub fn char_just_released(&self, c: char) -> bool {
+        self.logical_just_released(&LogicalKey::character(c))
+    }
+
+    /// What a physical key means in the current layout, if it's been pressed since the app started. winit has no way of
+    /// asking for the layout up front, so it's learned from key presses as they happen.
+    pub fn logical_key(&self, key: Key) -> Option<&LogicalKey> {
+        self.logical.layout.get(&key)
+    }
+
+    /// The physical key that produces a logical key in the current layout, as far as it's been learned (see
+    /// `logical_key`)
+    pub fn physical_key(&self, key: &LogicalKey) -> Option<Key> {
+        self.logical
+            .layout
+            .iter()
+            .find(|(_, logical)| *logical == key)
+            .map(|(physical, _)| *physical)
+    }
+
+    /// A name for a physical key to show to players, e.g. in "press [key] to jump". This is what the key types in the
+    /// current layout, which is only known once the key has been pressed (see `logical_key`). Until then this is None,
+    /// and `Key::us_name` is the best guess there is:
+    /// ```
+    /// # use renoir::prelude::*;
+    /// let input = RenoirInput::new();
+    /// let label = input
+    ///     .key_display_name(Key::Q)
+    ///     .unwrap_or_else(|| Key::Q.us_name());
+    /// assert_eq!(label, "Q");
+    /// ```
+    pub fn key_display_name(&self, key: Key) -> Option<String> {
+        self.logical_key(key).map(LogicalKey::to_string)
+    }
+}
+
+impl Key {
+    /// What the key types on a US keyboard, which could be wrong for other layouts (`Key::Q` is "A" on an AZERTY
+    /// keyboard). Prefer `RenoirInput::key_display_name` when it knows the key.
+    pub fn us_name(self) -> String {
+        let name = match self {
+            Key::Backquote => "`",
+            Key::Backslash => "\\",
+            Key::BracketLeft => "[",
+            Key::BracketRight => "]",
+            Key::Comma => ",",
+            Key::Equal => "=",
+            Key::Minus => "-",
+            Key::Period => ".",
+            Key::Quote => "'",
+            Key::Semicolon => ";",
+            Key::Slash => "/",
+            _ => {
+                let name = self.to_string();
+                return match name.strip_prefix("Digit") {
+                    Some(digit) => digit.to_owned(),
+                    None => name,
+                };
+            }
+        };
+        name.to_owned()
+    }
+}
+
+// without modifiers is what layouts are made of, and what makes Shift+1 the same key as 1
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub(super) fn unmodified_key(event: &winit::event::KeyEvent) -> winit::keyboard::Key {
+    use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+    event.key_without_modifiers()
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+pub(super) fn unmodified_key(event: &winit::event::KeyEvent) -> winit::keyboard::Key {
+    event.logical_key.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::input::InputEvent;
+
+    fn press(input: &mut RenoirInput, physical: Key, key: LogicalKey) {
+        input.press_key(physical);
+        input.inject(InputEvent::LogicalKey {
+            key,
+            physical: Some(physical),
+            pressed: true,
+        });
+    }
+
+    fn release(input: &mut RenoirInput, physical: Key, key: LogicalKey) {
+        input.release_key(physical);
+        input.inject(InputEvent::LogicalKey {
+            key,
+            physical: Some(physical),
+            pressed: false,
+        });
+    }
+
+    #[test]
+    fn display_names_are_only_known_once_learned() {
+        let mut input = RenoirInput::new();
+        assert_eq!(input.key_display_name(Key::Q), None);
+        assert_eq!(Key::Q.us_name(), "Q");
+        assert_eq!(Key::Digit1.us_name(), "1");
+        assert_eq!(Key::Semicolon.us_name(), ";");
+
+        press(&mut input, Key::Q, LogicalKey::character('a'));
+        assert_eq!(input.key_display_name(Key::Q).as_deref(), Some("A"));
+        assert_eq!(
+            input.physical_key(&LogicalKey::character('a')),
+            Some(Key::Q)
+        );
+    }
+
+    #[test]
+    fn released_as_what_it_was_pressed_as() {
+        let mut input = RenoirInput::new();
+
+        // Shift+1 goes down as '!', but Shift is let go first so it comes back up as '1'
+        press(&mut input, Key::Digit1, LogicalKey::character('!'));
+        assert!(input.char_just_pressed('!'));
+        input.update();
+        release(&mut input, Key::Digit1, LogicalKey::character('1'));
+        assert!(input.char_just_released('!'));
+    }
+}
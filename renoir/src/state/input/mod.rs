@@ -0,0 +1,713 @@
+mod actions;
+mod bindings;
+mod context;
+mod event;
+mod gamepad;
+mod logical;
+mod recording;
+mod timing;
+
+pub use actions::{ActionMap, AxisBinding, Binding};
+pub use bindings::BindingConflict;
+pub use context::InputContext;
+pub use event::InputEvent;
+#[cfg(feature = "gilrs")]
+pub use gamepad::GilrsBackend;
+pub use gamepad::{
+    GamepadAxis, GamepadBackend, GamepadButton, GamepadEvent, GamepadId, VirtualGamepad,
+};
+pub use logical::LogicalKey;
+pub use recording::{InputRecording, RecordedFrame};
+pub use timing::Combo;
+
+use context::ContextState;
+use gamepad::GamepadState;
+use logical::LogicalKeys;
+use recording::Recorder;
+use timing::InputTiming;
+
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+use winit::{
+    event::{ElementState, Ime, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::PhysicalKey,
+};
+
+/// Represents the state of a button (keyboard and mouse). JustPressed & JustReleased mean that the key's state has changed since the last processed engine tick.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum KeyState {
+    JustPressed,
+    Pressed,
+    JustReleased,
+    #[default]
+    Released,
+}
+
+impl KeyState {
+    pub fn pressed(&self) -> bool {
+        *self == Self::JustPressed || *self == Self::Pressed
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        *self == Self::JustPressed
+    }
+
+    pub fn released(&self) -> bool {
+        *self == Self::JustReleased || *self == Self::Released
+    }
+
+    pub fn just_released(&self) -> bool {
+        *self == Self::JustReleased
+    }
+
+    pub(crate) fn update(&self) -> Self {
+        match self {
+            KeyState::JustPressed => KeyState::Pressed,
+            KeyState::Pressed => KeyState::Pressed,
+            KeyState::JustReleased => KeyState::Released,
+            KeyState::Released => KeyState::Released,
+        }
+    }
+
+    // whether the button was down last tick
+    pub(crate) fn was_pressed(&self) -> bool {
+        *self == Self::Pressed || *self == Self::JustReleased
+    }
+
+    // what a button becomes when it's pressed or released
+    pub(crate) fn from_pressed(pressed: bool) -> Self {
+        if pressed {
+            KeyState::JustPressed
+        } else {
+            KeyState::JustReleased
+        }
+    }
+
+    pub(crate) fn from_transition(was_pressed: bool, pressed: bool) -> Self {
+        match (was_pressed, pressed) {
+            (false, true) => KeyState::JustPressed,
+            (true, true) => KeyState::Pressed,
+            (true, false) => KeyState::JustReleased,
+            (false, false) => KeyState::Released,
+        }
+    }
+}
+
+impl From<ElementState> for KeyState {
+    fn from(value: ElementState) -> Self {
+        match value {
+            ElementState::Pressed => KeyState::JustPressed,
+            ElementState::Released => KeyState::JustReleased,
+        }
+    }
+}
+
+// taken from winit::event::MouseButton
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    strum::EnumCount,
+    strum::FromRepr,
+    strum::Display,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
+#[allow(dead_code)]
+pub enum MouseBtn {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+}
+
+#[derive(Default)]
+pub struct MouseState {
+    // winit supports more mouse buttons than this but.... who cares about those buttons (for now (i should find a better way to represent this))
+    buttons: [KeyState; MouseBtn::COUNT],
+    cursor_delta: (f32, f32),
+    // in physical pixels, None until the cursor has moved over the window
+    cursor_position: Option<(f32, f32)>,
+    cursor_inside: bool,
+    scroll_delta: (f32, f32),
+    scroll_total: (f32, f32),
+}
+
+impl MouseState {
+    fn update(&mut self) {
+        self.buttons.iter_mut().for_each(|btn| {
+            *btn = btn.update();
+        });
+
+        self.cursor_delta = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
+    }
+}
+
+pub struct RenoirInput {
+    keys: [KeyState; Key::COUNT],
+    logical: LogicalKeys,
+    pub(crate) key_stream: Vec<(Key, KeyState)>,
+    prev_modifiers_state: winit::keyboard::ModifiersState,
+    pub(crate) mouse: MouseState,
+    /// Named actions and axes, see ActionMap
+    pub actions: ActionMap,
+    // from the lowest priority up, see InputContext
+    contexts: Vec<ContextState>,
+    gamepads: Vec<GamepadState>,
+    gamepad_connections: Vec<GamepadEvent>,
+    gamepad_backends: Vec<Box<dyn GamepadBackend>>,
+    gamepad_deadzone: f32,
+    // the window's, for converting the cursor position to logical pixels
+    scale_factor: f64,
+    // typed this frame, including key repeats and IME commits
+    text: String,
+    repeated_keys: Vec<Key>,
+    ime_active: bool,
+    ime_preedit: String,
+    ime_preedit_cursor: Option<(usize, usize)>,
+    recorder: Option<Recorder>,
+    playback: Option<std::vec::IntoIter<RecordedFrame>>,
+    timing: InputTiming,
+}
+
+impl RenoirInput {
+    pub fn new() -> Self {
+        RenoirInput {
+            keys: [KeyState::Released; Key::COUNT],
+            logical: LogicalKeys::default(),
+            key_stream: Vec::new(),
+            prev_modifiers_state: winit::keyboard::ModifiersState::empty(),
+            mouse: MouseState::default(),
+            actions: ActionMap::new(),
+            contexts: Vec::new(),
+            gamepads: Vec::new(),
+            gamepad_connections: Vec::new(),
+            gamepad_backends: Vec::new(),
+            gamepad_deadzone: 0.15,
+            scale_factor: 1.0,
+            text: String::new(),
+            repeated_keys: Vec::new(),
+            ime_active: false,
+            ime_preedit: String::new(),
+            ime_preedit_cursor: None,
+            recorder: None,
+            playback: None,
+            timing: InputTiming::default(),
+        }
+    }
+
+    pub(crate) fn process_events(&mut self, event: WindowEvent) {
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.set_mods(modifiers.state());
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                self.set_key(event);
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                self.set_mouse_button(state, button);
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.apply(InputEvent::CursorMoved {
+                    x: position.x as f32,
+                    y: position.y as f32,
+                });
+            }
+            WindowEvent::CursorEntered { .. } => self.apply(InputEvent::CursorEntered),
+            WindowEvent::CursorLeft { .. } => self.apply(InputEvent::CursorLeft),
+            WindowEvent::Ime(ime) => self.set_ime(ime),
+            _ => {}
+        }
+    }
+
+    /// Moves on to the next frame: JustPressed becomes Pressed, JustReleased becomes Released, and the per-frame
+    /// deltas and text are cleared. RenoirApp calls this after the main function, so it's only needed when driving
+    /// RenoirInput by hand (see `inject`).
+    pub fn update(&mut self) {
+        self.mouse.update();
+
+        self.keys.iter_mut().for_each(|key| {
+            *key = key.update();
+        });
+        self.logical.update();
+
+        self.key_stream.clear();
+        self.repeated_keys.clear();
+        self.text.clear();
+        self.timing.update();
+        self.update_contexts();
+        self.update_gamepads();
+    }
+
+    pub(crate) fn set_key(&mut self, input: winit::event::KeyEvent) {
+        if input.state == ElementState::Pressed {
+            if let Some(text) = &input.text {
+                self.apply(InputEvent::Text(text.to_string()));
+            }
+        }
+
+        let logical = LogicalKey::from_winit(&logical::unmodified_key(&input));
+        let physical = match input.physical_key {
+            // this unwraps safely as Key is a superset of KeyCode (see definition near EOF for more info)
+            PhysicalKey::Code(keycode) => Some(Key::from_repr(keycode as usize).unwrap()),
+            // unhandled by RenoirInput as of now, but I could add these to some sort of extra input field
+            PhysicalKey::Unidentified(_) => None,
+        };
+
+        // repeats leave the key state alone, otherwise a held key would keep going back to JustPressed
+        if input.repeat {
+            if let Some(key) = physical {
+                self.apply(InputEvent::KeyRepeat(key));
+            }
+            return;
+        }
+
+        let pressed = input.state.is_pressed();
+        if let Some(key) = physical {
+            self.apply(InputEvent::Key { key, pressed });
+        }
+        if let Some(key) = logical {
+            self.apply(InputEvent::LogicalKey {
+                key,
+                physical,
+                pressed,
+            });
+        }
+    }
+
+    pub(crate) fn set_ime(&mut self, ime: Ime) {
+        self.apply(match ime {
+            Ime::Enabled => InputEvent::ImeEnabled,
+            Ime::Preedit(text, cursor) => InputEvent::ImePreedit { text, cursor },
+            Ime::Commit(text) => InputEvent::ImeCommit(text),
+            Ime::Disabled => InputEvent::ImeDisabled,
+        });
+    }
+
+    pub(crate) fn set_mods(&mut self, mods: winit::keyboard::ModifiersState) {
+        let prev = self.prev_modifiers_state;
+        let changes = [
+            (Key::Shift, prev.shift_key(), mods.shift_key()),
+            (Key::Ctrl, prev.control_key(), mods.control_key()),
+            (Key::Alt, prev.alt_key(), mods.alt_key()),
+            (Key::Logo, prev.super_key(), mods.super_key()),
+        ];
+
+        for (key, was_pressed, pressed) in changes {
+            if was_pressed != pressed {
+                self.apply(InputEvent::Key { key, pressed });
+            }
+        }
+
+        self.prev_modifiers_state = mods;
+    }
+
+    pub(crate) fn set_mouse_button(&mut self, state: ElementState, button: MouseButton) {
+        let button = match button {
+            MouseButton::Left => MouseBtn::Left,
+            MouseButton::Right => MouseBtn::Right,
+            MouseButton::Middle => MouseBtn::Middle,
+            MouseButton::Back => MouseBtn::Back,
+            MouseButton::Forward => MouseBtn::Forward,
+            MouseButton::Other(_id) => return, /* currently unhandled */
+        };
+
+        self.apply(InputEvent::Mouse {
+            button,
+            pressed: state.is_pressed(),
+        });
+    }
+
+    pub(crate) fn set_cursor_delta(&mut self, delta: (f32, f32)) {
+        self.apply(InputEvent::MouseMotion {
+            dx: delta.0,
+            dy: delta.1,
+        });
+    }
+
+    pub(crate) fn set_scroll_delta(&mut self, delta: MouseScrollDelta) {
+        let (dx, dy) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (x, y),
+            MouseScrollDelta::PixelDelta(pos) => (pos.x as f32, pos.y as f32),
+        };
+
+        self.apply(InputEvent::Scroll { dx, dy });
+    }
+
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn get_key(&self, key: Key) -> KeyState {
+        self.keys[key as usize]
+    }
+
+    pub fn pressed(&self, key: Key) -> bool {
+        self.keys[key as usize].pressed()
+    }
+
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.keys[key as usize].just_pressed()
+    }
+
+    pub fn released(&self, key: Key) -> bool {
+        self.keys[key as usize].released()
+    }
+
+    pub fn just_released(&self, key: Key) -> bool {
+        self.keys[key as usize].just_released()
+    }
+
+    /// True on frames where the OS repeated a held key, e.g. for deleting more text while backspace is held
+    pub fn repeated(&self, key: Key) -> bool {
+        self.repeated_keys.contains(&key)
+    }
+
+    /// The text typed this frame, in order. This follows the keyboard layout, includes key repeats and IME input, and
+    /// leaves out control characters like backspace.
+    pub fn text_input(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether an IME (input method editor, used for typing e.g. Chinese or Japanese) is active, this requires
+    /// `WindowOptions::allow_ime`
+    pub fn ime_active(&self) -> bool {
+        self.ime_active
+    }
+
+    /// The text the IME is currently composing, which should be drawn where the text is being typed (usually
+    /// underlined) but not added to it yet. Once it's done the text shows up in `text_input`.
+    pub fn ime_preedit(&self) -> &str {
+        &self.ime_preedit
+    }
+
+    /// The cursor or selection within `ime_preedit`, as a byte range. None means the cursor should be hidden.
+    pub fn ime_preedit_cursor(&self) -> Option<(usize, usize)> {
+        self.ime_preedit_cursor
+    }
+
+    pub fn get_mouse_btn(&self, btn: MouseBtn) -> KeyState {
+        self.mouse.buttons[btn as usize]
+    }
+
+    pub fn mouse_pressed(&self, btn: MouseBtn) -> bool {
+        self.mouse.buttons[btn as usize].pressed()
+    }
+
+    pub fn mouse_just_pressed(&self, btn: MouseBtn) -> bool {
+        self.mouse.buttons[btn as usize].just_pressed()
+    }
+
+    pub fn mouse_released(&self, btn: MouseBtn) -> bool {
+        self.mouse.buttons[btn as usize].released()
+    }
+
+    pub fn mouse_just_released(&self, btn: MouseBtn) -> bool {
+        self.mouse.buttons[btn as usize].just_released()
+    }
+
+    pub fn get_mouse_delta(&self) -> (f32, f32) {
+        self.mouse.cursor_delta
+    }
+
+    /// Where the cursor is in physical pixels, relative to the top left of the window. This is the last position it
+    /// was seen at if it has left the window (see `cursor_in_window`), and None if it was never over the window.
+    pub fn get_cursor_position(&self) -> Option<(f32, f32)> {
+        self.mouse.cursor_position
+    }
+
+    /// Like `get_cursor_position`, but in logical pixels (physical pixels divided by the window's scale factor)
+    pub fn get_cursor_position_logical(&self) -> Option<(f32, f32)> {
+        let scale_factor = self.scale_factor as f32;
+        self.mouse
+            .cursor_position
+            .map(|(x, y)| (x / scale_factor, y / scale_factor))
+    }
+
+    pub fn cursor_in_window(&self) -> bool {
+        self.mouse.cursor_inside
+    }
+
+    /// How far the mouse wheel scrolled this frame. Most mice scroll in lines, but touchpads scroll in pixels, so the
+    /// units depend on the device.
+    pub fn get_scroll_delta(&self) -> (f32, f32) {
+        self.mouse.scroll_delta
+    }
+
+    /// The total distance scrolled since the app started, handy for zoom levels and scrolling lists
+    pub fn get_scroll_total(&self) -> (f32, f32) {
+        self.mouse.scroll_total
+    }
+}
+
+impl Default for RenoirInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Taken from winit::keyboard::KeyCode with additional modifiers, and "KeyA" changed to "A" for example
+// TODO: test if this works better than strum
+//#[repr(usize)]
+// the Display and FromStr names are the variant names, which is what binding files use
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    strum::EnumCount,
+    strum::FromRepr,
+    strum::Display,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
+#[allow(dead_code)]
+pub enum Key {
+    Backquote,
+    Backslash,
+    BracketLeft,
+    BracketRight,
+    Comma,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Equal,
+    IntlBackslash,
+    IntlRo,
+    IntlYen,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Minus,
+    Period,
+    Quote,
+    Semicolon,
+    Slash,
+    AltLeft,
+    AltRight,
+    Backspace,
+    CapsLock,
+    ContextMenu,
+    ControlLeft,
+    ControlRight,
+    Enter,
+    SuperLeft,
+    SuperRight,
+    ShiftLeft,
+    ShiftRight,
+    Space,
+    Tab,
+    Convert,
+    KanaMode,
+    Lang1,
+    Lang2,
+    Lang3,
+    Lang4,
+    Lang5,
+    NonConvert,
+    Delete,
+    End,
+    Help,
+    Home,
+    Insert,
+    PageDown,
+    PageUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadBackspace,
+    NumpadClear,
+    NumpadClearEntry,
+    NumpadComma,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadEnter,
+    NumpadEqual,
+    NumpadHash,
+    NumpadMemoryAdd,
+    NumpadMemoryClear,
+    NumpadMemoryRecall,
+    NumpadMemoryStore,
+    NumpadMemorySubtract,
+    NumpadMultiply,
+    NumpadParenLeft,
+    NumpadParenRight,
+    NumpadStar,
+    NumpadSubtract,
+    Escape,
+    Fn,
+    FnLock,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    BrowserBack,
+    BrowserFavorites,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
+    BrowserSearch,
+    BrowserStop,
+    Eject,
+    LaunchApp1,
+    LaunchApp2,
+    LaunchMail,
+    MediaPlayPause,
+    MediaSelect,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    Power,
+    Sleep,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+    WakeUp,
+    Meta,
+    Hyper,
+    Turbo,
+    Abort,
+    Resume,
+    Suspend,
+    Again,
+    Copy,
+    Cut,
+    Find,
+    Open,
+    Paste,
+    Props,
+    Select,
+    Undo,
+    Hiragana,
+    Katakana,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    F25,
+    F26,
+    F27,
+    F28,
+    F29,
+    F30,
+    F31,
+    F32,
+    F33,
+    F34,
+    F35,
+    // new renoir stuff here, these are useful for not specifying ShiftL or ShiftR, for example. 'Logo' is the windows key or mac's Command
+    Shift,
+    Ctrl,
+    Alt,
+    Logo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_move_on_each_update() {
+        let mut input = RenoirInput::new();
+
+        input.press_key(Key::A);
+        assert_eq!(input.get_key(Key::A), KeyState::JustPressed);
+        input.update();
+        assert_eq!(input.get_key(Key::A), KeyState::Pressed);
+        input.update();
+        assert_eq!(input.get_key(Key::A), KeyState::Pressed);
+
+        input.release_key(Key::A);
+        assert_eq!(input.get_key(Key::A), KeyState::JustReleased);
+        input.update();
+        assert_eq!(input.get_key(Key::A), KeyState::Released);
+    }
+
+    #[test]
+    fn mouse_buttons_move_on_each_update() {
+        let mut input = RenoirInput::new();
+
+        input.press_mouse_btn(MouseBtn::Left);
+        assert!(input.mouse_just_pressed(MouseBtn::Left));
+        input.update();
+        assert_eq!(input.get_mouse_btn(MouseBtn::Left), KeyState::Pressed);
+
+        input.release_mouse_btn(MouseBtn::Left);
+        assert!(input.mouse_just_released(MouseBtn::Left));
+        input.update();
+        assert_eq!(input.get_mouse_btn(MouseBtn::Left), KeyState::Released);
+    }
+}
@@ -0,0 +1,346 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+
+use super::{InputEvent, Key, MouseBtn, RenoirInput};
+
+/// Everything that happened to the input in one frame, and how long the frame took
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// The frame's unscaled delta time, in seconds
+    pub delta_time: f32,
+    pub events: Vec<InputEvent>,
+}
+
+/// A frame by frame recording of input, made with `RenoirInput::start_recording` or `RenoirApp::record_input`.
+/// Playing it back with `RenoirApp::play_input` feeds the same events and delta times to the game as when it was
+/// recorded, so as long as the game itself is deterministic, it does exactly the same thing.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    // a recording that's written as it goes (see start_recording_to) has no frames until the first one is done
+    #[serde(default)]
+    frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// The number of recorded frames
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The total unscaled time the recording covers, in seconds
+    pub fn duration(&self) -> f64 {
+        self.frames
+            .iter()
+            .map(|frame| frame.delta_time as f64)
+            .sum()
+    }
+
+    pub fn push_frame(&mut self, frame: RecordedFrame) {
+        self.frames.push(frame);
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read input recording from {}", path.display()))?;
+        toml::from_str(&toml)
+            .with_context(|| format!("invalid input recording in {}", path.display()))
+    }
+
+    /// Writes the recording to a (TOML) file, creating its directory if it doesn't exist
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        create_parent_dir(path)?;
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to save input recording to {}", path.display()))
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Recorder {
+    recording: InputRecording,
+    // the events since the last frame was recorded
+    pub(crate) events: Vec<InputEvent>,
+    // where frames are appended as they're recorded, see start_recording_to
+    file: Option<File>,
+}
+
+impl Recorder {
+    // each frame is its own [[frames]] table, so appending them one at a time builds up the same file `save` writes
+    fn append(file: &mut File, frame: &RecordedFrame) -> Result<()> {
+        #[derive(Serialize)]
+        struct Chunk<'a> {
+            frames: [&'a RecordedFrame; 1],
+        }
+
+        let toml = toml::to_string(&Chunk { frames: [frame] })?;
+        file.write_all(toml.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl RenoirInput {
+    /// Starts recording every input event along with each frame's delta time. Recordings are meant to be played back
+    /// from the start of the app, use `RenoirApp::record_input` to record a whole session.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Recorder::default());
+    }
+
+    /// Like `start_recording`, but every frame is also written to a (TOML) file as soon as it's recorded, so nothing is
+    /// lost if the app crashes. The file can be loaded with `InputRecording::load` at any point, and its directory is
+    /// created if it doesn't exist.
+    pub fn start_recording_to(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        create_parent_dir(path)?;
+        let file = File::create(path)
+            .with_context(|| format!("failed to create input recording {}", path.display()))?;
+
+        self.recorder = Some(Recorder {
+            file: Some(file),
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    /// Stops recording and returns what was recorded, if anything was being recorded
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recorder.take().map(|recorder| recorder.recording)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Replaces live input with a recording, one recorded frame per frame. Live input is ignored until the recording
+    /// runs out. RenoirApp also replays delta time from the recording, see `RenoirApp::play_input`.
+    pub fn start_playback(&mut self, recording: InputRecording) {
+        self.playback = Some(recording.frames.into_iter());
+    }
+
+    pub fn stop_playback(&mut self) {
+        self.playback = None;
+    }
+
+    pub fn is_playing_back(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    // called by RenoirApp once per frame, after all of the frame's events have been applied
+    pub(crate) fn record_frame(&mut self, delta_time: f32) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            let events = std::mem::take(&mut recorder.events);
+            let frame = RecordedFrame { delta_time, events };
+
+            if let Some(file) = recorder.file.as_mut() {
+                // unbuffered, so every frame reaches the OS right away
+                if let Err(e) = Recorder::append(file, &frame) {
+                    log::error!(
+                        "failed to write input recording, the rest is only kept in memory: {e:#}"
+                    );
+                    recorder.file = None;
+                }
+            }
+            recorder.recording.push_frame(frame);
+        }
+    }
+
+    // applies the next recorded frame's events and returns its delta time, or None once the recording is over
+    pub(crate) fn play_frame(&mut self) -> Option<f32> {
+        let frame = self.playback.as_mut()?.next();
+        let Some(frame) = frame else {
+            self.playback = None;
+            self.release_held_input();
+            return None;
+        };
+
+        for event in frame.events {
+            self.apply(event);
+        }
+        Some(frame.delta_time)
+    }
+
+    // a recording can end with keys still held down, and live input never saw them go down so it wouldn't ever release
+    // them either
+    fn release_held_input(&mut self) {
+        let keys = (0..Key::COUNT)
+            .filter_map(Key::from_repr)
+            .filter(|&key| self.pressed(key))
+            .map(|key| InputEvent::Key {
+                key,
+                pressed: false,
+            });
+        let logical_keys =
+            self.held_logical_keys()
+                .into_iter()
+                .map(|(physical, key)| InputEvent::LogicalKey {
+                    key,
+                    physical: Some(physical),
+                    pressed: false,
+                });
+        let mouse_btns = (0..MouseBtn::COUNT)
+            .filter_map(MouseBtn::from_repr)
+            .filter(|&button| self.mouse_pressed(button))
+            .map(|button| InputEvent::Mouse {
+                button,
+                pressed: false,
+            });
+        let gamepads = self
+            .gamepad_release_events()
+            .into_iter()
+            .map(InputEvent::Gamepad);
+
+        let events = keys
+            .chain(logical_keys)
+            .chain(mouse_btns)
+            .chain(gamepads)
+            .collect::<Vec<_>>();
+        for event in events {
+            self.apply(event);
+        }
+    }
+}
+
+// also used when saving bindings
+pub(super) fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::input::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId, LogicalKey};
+
+    fn record_some_input(input: &mut RenoirInput) {
+        input.press_key(Key::W);
+        input.inject(InputEvent::LogicalKey {
+            key: LogicalKey::character('z'),
+            physical: Some(Key::W),
+            pressed: true,
+        });
+        input.move_mouse(3.0, -1.5);
+        input.record_frame(0.016);
+        input.update();
+
+        input.press_mouse_btn(MouseBtn::Left);
+        input.type_text("hi");
+        input.record_frame(0.017);
+        input.update();
+
+        input.record_frame(0.5);
+        input.update();
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir()
+            .join(format!("renoir_recording_{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut input = RenoirInput::new();
+        input.start_recording();
+        record_some_input(&mut input);
+        let recording = input.stop_recording().unwrap();
+        assert_eq!(recording.len(), 3);
+        assert_eq!(recording.frames()[2].events, Vec::new());
+
+        let path = temp_path("round_trip.toml");
+        recording.save(&path).unwrap();
+        assert_eq!(InputRecording::load(&path).unwrap(), recording);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn frames_are_written_as_they_are_recorded() {
+        let path = temp_path("incremental.toml");
+        let mut input = RenoirInput::new();
+        input.start_recording_to(&path).unwrap();
+        assert!(InputRecording::load(&path).unwrap().is_empty());
+
+        record_some_input(&mut input);
+        // read back while still recording, like after a crash
+        let written = InputRecording::load(&path).unwrap();
+        assert_eq!(Some(written), input.stop_recording());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn playback_replays_events_and_releases_held_input_at_the_end() {
+        let mut recorder = RenoirInput::new();
+        recorder.start_recording();
+        record_some_input(&mut recorder);
+        let mut recording = recorder.stop_recording().unwrap();
+        let pad = GamepadId(0);
+        recording.push_frame(RecordedFrame {
+            delta_time: 0.016,
+            events: vec![
+                InputEvent::Gamepad(GamepadEvent::Connected(pad)),
+                InputEvent::Gamepad(GamepadEvent::Button {
+                    id: pad,
+                    button: GamepadButton::South,
+                    pressed: true,
+                }),
+                InputEvent::Gamepad(GamepadEvent::Axis {
+                    id: pad,
+                    axis: GamepadAxis::LeftStickX,
+                    value: 1.0,
+                }),
+            ],
+        });
+
+        let mut input = RenoirInput::new();
+        input.start_playback(recording);
+
+        assert_eq!(input.play_frame(), Some(0.016));
+        assert!(input.just_pressed(Key::W));
+        assert!(input.char_just_pressed('z'));
+        assert_eq!(input.get_mouse_delta(), (3.0, -1.5));
+        input.update();
+        assert_eq!(input.play_frame(), Some(0.017));
+        assert!(input.mouse_just_pressed(MouseBtn::Left));
+        input.update();
+        assert_eq!(input.play_frame(), Some(0.5));
+        input.update();
+        assert_eq!(input.play_frame(), Some(0.016));
+        assert!(input.get_gamepad_btn(pad, GamepadButton::South).pressed());
+        input.update();
+
+        // the recording is over, and nothing it held down stays stuck
+        assert_eq!(input.play_frame(), None);
+        assert!(!input.is_playing_back());
+        assert!(input.just_released(Key::W));
+        assert!(input.char_just_released('z'));
+        assert!(input.mouse_just_released(MouseBtn::Left));
+        assert!(input
+            .get_gamepad_btn(pad, GamepadButton::South)
+            .just_released());
+        assert_eq!(input.gamepad_axis(pad, GamepadAxis::LeftStickX), 0.0);
+
+        input.update();
+        assert!(input.released(Key::W));
+        assert!(!input.char_pressed('z'));
+    }
+}
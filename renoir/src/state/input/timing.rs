@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+
+use strum::EnumCount;
+
+use super::{Binding, Key, KeyState, MouseBtn, RenoirInput};
+
+/// A sequence of inputs that has to be pressed in order, each step within a time window of the last one, e.g. a
+/// fighting game special move:
+/// ```
+/// # use renoir::prelude::*;
+/// let hadouken = Combo::new()
+///     .then(Key::ArrowDown)
+///     .then_together([Key::ArrowDown, Key::ArrowRight])
+///     .then(Key::J)
+///     .with_step_window(0.2);
+///
+/// let mut input = RenoirInput::new();
+/// input.add_combo("hadouken", hadouken);
+///
+/// // RenoirApp does this for you, the time comes from DeltaTime
+/// for (time, key) in [(0.0, Key::ArrowDown), (0.1, Key::ArrowRight), (0.2, Key::J)] {
+///     input.press_key(key);
+///     input.set_time(time);
+///     input.update();
+/// }
+/// assert!(input.consume_combo("hadouken"));
+/// assert!(!input.combo_triggered("hadouken"));
+/// ```
+/// Each step happens on the frame its last button goes down, and inputs that aren't part of the combo are ignored
+/// rather than breaking it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Combo {
+    steps: Vec<Vec<Binding>>,
+    step_window: f32,
+    buffer: f32,
+}
+
+impl Combo {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Adds a step that's a single button
+    pub fn then(mut self, binding: impl Into<Binding>) -> Self {
+        self.steps.push(vec![binding.into()]);
+        self
+    }
+
+    /// Adds a step where all of the buttons have to be held at once, like down-forward
+    pub fn then_together<B: Into<Binding>>(
+        mut self,
+        bindings: impl IntoIterator<Item = B>,
+    ) -> Self {
+        self.steps
+            .push(bindings.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// The most time in seconds that can pass between two steps before the combo starts over (0.25 by default).
+    /// Negative or NaN windows are ignored.
+    pub fn with_step_window(mut self, seconds: f32) -> Self {
+        if seconds.is_nan() || seconds < 0.0 {
+            log::warn!("ignoring invalid combo step window {seconds}");
+            return self;
+        }
+        self.step_window = seconds;
+        self
+    }
+
+    /// How long in seconds the combo counts as triggered after it's completed, unless it's consumed first (0.1 by
+    /// default). This lets a combo finished just before the game can act on it (mid-animation, say) still go through.
+    pub fn with_buffer(mut self, seconds: f32) -> Self {
+        if seconds.is_nan() || seconds < 0.0 {
+            log::warn!("ignoring invalid combo buffer {seconds}");
+            return self;
+        }
+        self.buffer = seconds;
+        self
+    }
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            step_window: 0.25,
+            buffer: 0.1,
+        }
+    }
+}
+
+struct ComboState {
+    combo: Combo,
+    next_step: usize,
+    last_step_at: f64,
+    triggered_at: Option<f64>,
+}
+
+// everything to do with when inputs happened, stamped with the times RenoirApp passes to set_time
+pub(crate) struct InputTiming {
+    time: f64,
+    key_pressed_at: [Option<f64>; Key::COUNT],
+    mouse_pressed_at: [Option<f64>; MouseBtn::COUNT],
+    // the first click of a possible double click
+    last_click: [Option<f64>; MouseBtn::COUNT],
+    double_clicked: [bool; MouseBtn::COUNT],
+    double_click_window: f32,
+    combos: HashMap<String, ComboState>,
+}
+
+impl Default for InputTiming {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            key_pressed_at: [None; Key::COUNT],
+            mouse_pressed_at: [None; MouseBtn::COUNT],
+            last_click: [None; MouseBtn::COUNT],
+            double_clicked: [false; MouseBtn::COUNT],
+            double_click_window: 0.3,
+            combos: HashMap::new(),
+        }
+    }
+}
+
+impl InputTiming {
+    pub(crate) fn update(&mut self) {
+        self.double_clicked = [false; MouseBtn::COUNT];
+    }
+}
+
+impl RenoirInput {
+    /// Tells input what time it is (in seconds), which timestamps everything pressed since the last update and
+    /// advances combos. RenoirApp calls this every frame with `DeltaTime::unscaled_elapsed` before the main function,
+    /// so like `update` it's only needed when driving RenoirInput by hand.
+    pub fn set_time(&mut self, seconds: f64) {
+        self.timing.time = seconds;
+
+        for &(key, state) in &self.key_stream {
+            if state.just_pressed() {
+                self.timing.key_pressed_at[key as usize] = Some(seconds);
+            }
+        }
+
+        for (i, btn) in self.mouse.buttons.iter().enumerate() {
+            if !btn.just_pressed() {
+                continue;
+            }
+
+            self.timing.mouse_pressed_at[i] = Some(seconds);
+
+            let window = self.timing.double_click_window as f64;
+            self.timing.double_clicked[i] =
+                self.timing.last_click[i].is_some_and(|at| seconds - at <= window);
+            // a third click starts a new double click rather than counting as another one
+            self.timing.last_click[i] = (!self.timing.double_clicked[i]).then_some(seconds);
+        }
+
+        self.advance_combos();
+    }
+
+    fn advance_combos(&mut self) {
+        let now = self.timing.time;
+
+        // taken out so that binding states can be read while the combos are changed
+        let mut combos = std::mem::take(&mut self.timing.combos);
+        for state in combos.values_mut() {
+            let combo = &state.combo;
+            if combo.steps.is_empty() {
+                continue;
+            }
+
+            if state.next_step > 0 && now - state.last_step_at > combo.step_window as f64 {
+                state.next_step = 0;
+            }
+
+            if self.step_hit(&combo.steps[state.next_step]) {
+                state.next_step += 1;
+                state.last_step_at = now;
+            } else if state.next_step > 0 && self.step_hit(&combo.steps[0]) {
+                // a fresh attempt at the combo
+                state.next_step = 1;
+                state.last_step_at = now;
+            }
+
+            if state.next_step == combo.steps.len() {
+                state.triggered_at = Some(now);
+                state.next_step = 0;
+            }
+        }
+        self.timing.combos = combos;
+    }
+
+    // a step happens on the frame its last button goes down while the rest are held
+    fn step_hit(&self, step: &[Binding]) -> bool {
+        let states = step
+            .iter()
+            .map(|binding| self.binding_state(binding))
+            .collect::<Vec<_>>();
+
+        states.iter().all(KeyState::pressed) && states.iter().any(KeyState::just_pressed)
+    }
+
+    pub fn add_combo(&mut self, name: impl Into<String>, combo: Combo) {
+        self.timing.combos.insert(
+            name.into(),
+            ComboState {
+                combo,
+                next_step: 0,
+                last_step_at: 0.0,
+                triggered_at: None,
+            },
+        );
+    }
+
+    pub fn remove_combo(&mut self, name: &str) {
+        self.timing.combos.remove(name);
+    }
+
+    /// Whether the combo was completed within its buffer time and hasn't been consumed
+    pub fn combo_triggered(&self, name: &str) -> bool {
+        self.timing.combos.get(name).is_some_and(|state| {
+            state
+                .triggered_at
+                .is_some_and(|at| self.timing.time - at <= state.combo.buffer as f64)
+        })
+    }
+
+    /// Like `combo_triggered`, but also clears the combo so that it only triggers once
+    pub fn consume_combo(&mut self, name: &str) -> bool {
+        let triggered = self.combo_triggered(name);
+        if let Some(state) = self.timing.combos.get_mut(name) {
+            state.triggered_at = None;
+        }
+        triggered
+    }
+
+    /// How long in seconds a key has been held down, 0.0 if it isn't pressed
+    pub fn held_for(&self, key: Key) -> f32 {
+        if self.pressed(key) {
+            self.since(self.timing.key_pressed_at[key as usize])
+        } else {
+            0.0
+        }
+    }
+
+    pub fn held_at_least(&self, key: Key, seconds: f32) -> bool {
+        self.pressed(key) && self.held_for(key) >= seconds
+    }
+
+    /// Whether a key was pressed in the last `seconds`, even if it has been released since. This is the usual way of
+    /// buffering an input, e.g. still jumping if jump was pressed just before landing.
+    pub fn pressed_within(&self, key: Key, seconds: f32) -> bool {
+        self.timing.key_pressed_at[key as usize]
+            .is_some_and(|at| self.timing.time - at <= seconds as f64)
+    }
+
+    /// How long in seconds a mouse button has been held down, 0.0 if it isn't pressed
+    pub fn mouse_held_for(&self, btn: MouseBtn) -> f32 {
+        if self.mouse_pressed(btn) {
+            self.since(self.timing.mouse_pressed_at[btn as usize])
+        } else {
+            0.0
+        }
+    }
+
+    /// True on the frame a mouse button is clicked for the second time within the double click window
+    pub fn mouse_double_clicked(&self, btn: MouseBtn) -> bool {
+        self.timing.double_clicked[btn as usize]
+    }
+
+    pub fn double_click_window(&self) -> f32 {
+        self.timing.double_click_window
+    }
+
+    /// The most time in seconds there can be between the clicks of a double click (0.3 by default). Negative or NaN
+    /// windows are ignored.
+    pub fn set_double_click_window(&mut self, seconds: f32) {
+        if seconds.is_nan() || seconds < 0.0 {
+            log::warn!("ignoring invalid double click window {seconds}");
+            return;
+        }
+        self.timing.double_click_window = seconds;
+    }
+
+    fn since(&self, time: Option<f64>) -> f32 {
+        time.map_or(0.0, |at| (self.timing.time - at) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // one frame of RenoirApp: input arrives, the time is set, the game runs and then input is updated
+    fn frame(input: &mut RenoirInput, time: f64, events: impl FnOnce(&mut RenoirInput)) {
+        events(input);
+        input.set_time(time);
+    }
+
+    fn click(input: &mut RenoirInput, time: f64) -> bool {
+        frame(input, time, |input| input.press_mouse_btn(MouseBtn::Left));
+        let double_clicked = input.mouse_double_clicked(MouseBtn::Left);
+        input.update();
+        input.release_mouse_btn(MouseBtn::Left);
+        input.update();
+        double_clicked
+    }
+
+    #[test]
+    fn double_clicks_need_both_clicks_within_the_window() {
+        let mut input = RenoirInput::new();
+
+        assert!(!click(&mut input, 0.0));
+        assert!(click(&mut input, 0.2));
+        // the third click of a triple click starts over rather than being another double click
+        assert!(!click(&mut input, 0.4));
+        assert!(click(&mut input, 0.6));
+
+        assert!(!click(&mut input, 2.0));
+        assert!(!click(&mut input, 2.5));
+    }
+
+    #[test]
+    fn double_clicked_only_lasts_one_frame() {
+        let mut input = RenoirInput::new();
+        click(&mut input, 0.0);
+        frame(&mut input, 0.1, |input| {
+            input.press_mouse_btn(MouseBtn::Left)
+        });
+        assert!(input.mouse_double_clicked(MouseBtn::Left));
+
+        input.update();
+        assert!(!input.mouse_double_clicked(MouseBtn::Left));
+    }
+
+    #[test]
+    fn held_for_and_pressed_within() {
+        let mut input = RenoirInput::new();
+        frame(&mut input, 1.0, |input| input.press_key(Key::Space));
+        input.update();
+        frame(&mut input, 1.5, |_| {});
+
+        assert_eq!(input.held_for(Key::Space), 0.5);
+        assert!(input.held_at_least(Key::Space, 0.5));
+        assert!(!input.held_at_least(Key::Space, 0.6));
+        assert_eq!(input.held_for(Key::W), 0.0);
+
+        // buffered presses are remembered after the key is let go, but not forever
+        input.update();
+        frame(&mut input, 1.6, |input| input.release_key(Key::Space));
+        assert_eq!(input.held_for(Key::Space), 0.0);
+        assert!(input.pressed_within(Key::Space, 0.6));
+        assert!(!input.pressed_within(Key::Space, 0.5));
+        assert!(!input.pressed_within(Key::W, 10.0));
+    }
+
+    fn press_and_release(input: &mut RenoirInput, time: f64, key: Key) {
+        frame(input, time, |input| input.press_key(key));
+        input.update();
+        input.release_key(key);
+        input.update();
+    }
+
+    #[test]
+    fn combos_start_over_when_a_step_is_too_late() {
+        let mut input = RenoirInput::new();
+        input.add_combo(
+            "dash",
+            Combo::new()
+                .then(Key::D)
+                .then(Key::D)
+                .then(Key::J)
+                .with_step_window(0.2),
+        );
+
+        press_and_release(&mut input, 0.0, Key::D);
+        press_and_release(&mut input, 0.1, Key::D);
+        press_and_release(&mut input, 0.5, Key::J);
+        assert!(!input.combo_triggered("dash"));
+
+        // unrelated keys in between don't break it
+        press_and_release(&mut input, 1.0, Key::D);
+        press_and_release(&mut input, 1.1, Key::W);
+        press_and_release(&mut input, 1.2, Key::D);
+        frame(&mut input, 1.3, |input| input.press_key(Key::J));
+        assert!(input.combo_triggered("dash"));
+    }
+
+    #[test]
+    fn triggered_combos_expire_after_their_buffer() {
+        let mut input = RenoirInput::new();
+        input.add_combo(
+            "uppercut",
+            Combo::new().then(Key::S).then(Key::K).with_buffer(0.1),
+        );
+
+        press_and_release(&mut input, 0.0, Key::S);
+        press_and_release(&mut input, 0.1, Key::K);
+        frame(&mut input, 0.15, |_| {});
+        assert!(input.combo_triggered("uppercut"));
+
+        frame(&mut input, 0.3, |_| {});
+        assert!(!input.combo_triggered("uppercut"));
+        assert!(!input.consume_combo("uppercut"));
+        assert!(!input.combo_triggered("missing"));
+    }
+
+    #[test]
+    fn invalid_windows_are_ignored() {
+        let mut input = RenoirInput::new();
+        input.set_double_click_window(0.5);
+        input.set_double_click_window(-1.0);
+        input.set_double_click_window(f32::NAN);
+        assert_eq!(input.double_click_window(), 0.5);
+
+        let combo = Combo::new().with_step_window(0.5).with_buffer(0.2);
+        assert_eq!(
+            combo.clone().with_step_window(f32::NAN).with_buffer(-1.0),
+            combo
+        );
+    }
+}
@@ -1,6 +1,9 @@
 pub mod app_state;
 pub mod camera;
+pub mod capture;
 pub mod flow;
+pub mod frame_stats;
 pub mod input;
 pub mod time;
+pub mod window_info;
 pub mod window_options;
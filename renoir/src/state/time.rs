@@ -1,8 +1,95 @@
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A source of time for DeltaTime. The default is SystemClock, but anything that needs exact, repeatable timings
+/// (tests, replays) can swap in a ManualClock with `DeltaTime::set_clock`.
+pub trait Clock {
+    /// Time since some fixed starting point, which must never go backwards
+    fn now(&self) -> Duration;
+}
+
+/// The real clock, based on std's Instant.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so keep one around to advance it after
+/// handing a clone to DeltaTime:
+/// ```
+/// # use renoir::prelude::*;
+/// # use std::time::Duration;
+/// let clock = ManualClock::new();
+/// let mut time = DeltaTime::with_clock(clock.clone());
+///
+/// clock.advance(Duration::from_millis(16));
+/// time.update();
+/// assert_eq!(time.delta_time(), 0.016);
+/// ```
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn advance_secs(&self, seconds: f32) {
+        self.advance(Duration::from_secs_f32(seconds));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+}
 
 pub struct DeltaTime {
     delta_time: f32,
-    prev_time: Instant,
+    unscaled_delta_time: f32,
+    raw_delta_time: f32,
+    clock: Box<dyn Clock>,
+    prev_time: Duration,
+    elapsed: f64,
+    unscaled_elapsed: f64,
+    frame_count: u64,
+    time_scale: f32,
+    paused: bool,
+    max_delta_time: f32,
+    // fixed timestep state, see RenoirApp::fixed_update
+    fixed_delta_time: f32,
+    max_substeps: u32,
+    accumulator: f32,
 }
 
 impl DeltaTime {
@@ -12,21 +99,296 @@ impl DeltaTime {
         }
     }
 
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
+        let mut time = Self::new();
+        time.set_clock(clock);
+        time
+    }
+
+    /// Replaces the clock delta time is measured with, the next update measures from the moment this was called
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.prev_time = clock.now();
+        self.clock = Box::new(clock);
+    }
+
     pub fn update(&mut self) {
-        self.delta_time = self.prev_time.elapsed().as_secs_f32();
-        self.prev_time = Instant::now();
+        let now = self.clock.now();
+        let seconds = now.saturating_sub(self.prev_time).as_secs_f32();
+        self.prev_time = now;
+        self.advance(seconds);
+    }
+
+    // used instead of update when replaying an input recording, so that every frame takes exactly as long as it did
+    // when it was recorded
+    pub(crate) fn replay(&mut self, seconds: f32) {
+        // keep up with the clock anyway, otherwise the first frame after the replay would be as long as the replay
+        self.prev_time = self.clock.now();
+        self.advance(seconds);
+    }
+
+    fn advance(&mut self, seconds: f32) {
+        self.raw_delta_time = seconds;
+        // clamped so that a long stall (a debugger breakpoint, dragging the window, etc.) doesn't turn into one huge step
+        self.unscaled_delta_time = seconds.min(self.max_delta_time);
+
+        self.delta_time = if self.paused {
+            0.
+        } else {
+            self.unscaled_delta_time * self.time_scale
+        };
+
+        self.elapsed += self.delta_time as f64;
+        self.unscaled_elapsed += self.unscaled_delta_time as f64;
+        self.frame_count += 1;
     }
 
+    /// Seconds since the last frame, scaled by the time scale (and 0 while paused). This is what gameplay should use.
     pub fn delta_time(&self) -> f32 {
         self.delta_time
     }
+
+    /// Seconds since the last frame regardless of time scale or pausing, useful for UI and menus
+    pub fn unscaled_delta_time(&self) -> f32 {
+        self.unscaled_delta_time
+    }
+
+    /// Seconds since the last frame as it was measured, without being clamped to max_delta_time. Gameplay shouldn't
+    /// use this, but it shows how long a hitch really was (which is what FrameStats records).
+    pub fn raw_delta_time(&self) -> f32 {
+        self.raw_delta_time
+    }
+
+    /// Total scaled game time in seconds, this doesn't advance while paused
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Total time in seconds since the app started, regardless of time scale or pausing
+    pub fn unscaled_elapsed(&self) -> f64 {
+        self.unscaled_elapsed
+    }
+
+    /// The number of frames that have been run so far
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Multiplies delta_time (and so how fast fixed updates happen), e.g. 0.5 for half speed slow-motion. Negative or
+    /// non-finite scales are ignored.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        if !(time_scale >= 0.0 && time_scale.is_finite()) {
+            log::warn!("ignoring invalid time scale {time_scale}");
+            return;
+        }
+        self.time_scale = time_scale;
+    }
+
+    /// Stops game time, delta_time will be 0 and fixed updates won't run until resume is called
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn max_delta_time(&self) -> f32 {
+        self.max_delta_time
+    }
+
+    /// The longest a single frame can be (in seconds) before delta_time gets clamped, this has to be positive but can
+    /// be `f32::INFINITY` to never clamp. Anything else is ignored.
+    pub fn set_max_delta_time(&mut self, seconds: f32) {
+        if seconds.is_nan() || seconds <= 0.0 {
+            log::warn!("ignoring invalid max delta time {seconds}");
+            return;
+        }
+        self.max_delta_time = seconds;
+    }
+
+    /// The time in seconds between fixed updates, this is what fixed_update should use instead of delta_time
+    pub fn fixed_delta_time(&self) -> f32 {
+        self.fixed_delta_time
+    }
+
+    /// Sets how often fixed_update runs, in updates per second (Hz). Rates that aren't positive and finite are ignored.
+    pub fn set_fixed_rate(&mut self, hz: f32) {
+        if !(hz > 0.0 && hz.is_finite()) {
+            log::warn!("ignoring invalid fixed update rate {hz} Hz");
+            return;
+        }
+        self.set_fixed_delta_time(1.0 / hz);
+    }
+
+    /// Sets the time in seconds between fixed updates. Times that aren't positive and finite are ignored.
+    pub fn set_fixed_delta_time(&mut self, seconds: f32) {
+        if !(seconds > 0.0 && seconds.is_finite()) {
+            log::warn!("ignoring invalid fixed delta time {seconds}");
+            return;
+        }
+        self.fixed_delta_time = seconds;
+    }
+
+    pub fn max_substeps(&self) -> u32 {
+        self.max_substeps
+    }
+
+    /// Limits how many fixed updates can run in a single frame. If a frame takes so long that more would be needed,
+    /// the leftover time is dropped so that the game slows down instead of falling further and further behind.
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps;
+    }
+
+    /// How far the current frame is between the last fixed update and the next one, from 0.0 to 1.0.
+    /// Rendering can use this to blend between the previous and current fixed states, e.g.
+    /// `prev_pos.lerp(&pos, ren.time.alpha())`
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.fixed_delta_time
+    }
+
+    // adds this frame's delta time to the accumulator and returns how many fixed updates need to run to catch up
+    pub(crate) fn fixed_steps(&mut self) -> u32 {
+        self.accumulator += self.delta_time;
+
+        let steps = (self.accumulator / self.fixed_delta_time) as u32;
+        if steps > self.max_substeps {
+            // keep the fractional part so alpha stays meaningful, but throw away the rest
+            self.accumulator %= self.fixed_delta_time;
+            return self.max_substeps;
+        }
+
+        self.accumulator -= steps as f32 * self.fixed_delta_time;
+        steps
+    }
 }
 
 impl Default for DeltaTime {
     fn default() -> Self {
         Self {
             delta_time: 0.,
-            prev_time: Instant::now(),
+            unscaled_delta_time: 0.,
+            raw_delta_time: 0.,
+            clock: Box::new(SystemClock::new()),
+            prev_time: Duration::ZERO,
+            elapsed: 0.,
+            unscaled_elapsed: 0.,
+            frame_count: 0,
+            time_scale: 1.,
+            paused: false,
+            max_delta_time: 0.25,
+            fixed_delta_time: 1. / 60.,
+            max_substeps: 5,
+            accumulator: 0.,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_drives_delta_time() {
+        let clock = ManualClock::new();
+        let mut time = DeltaTime::with_clock(clock.clone());
+
+        time.update();
+        assert_eq!(time.delta_time(), 0.0);
+
+        clock.advance(Duration::from_millis(20));
+        time.update();
+        assert_eq!(time.delta_time(), 0.02);
+        assert_eq!(time.frame_count(), 2);
+    }
+
+    #[test]
+    fn fixed_steps_catch_up_and_leave_alpha() {
+        let clock = ManualClock::new();
+        let mut time = DeltaTime::with_clock(clock.clone());
+        time.set_fixed_delta_time(0.25);
+
+        clock.advance(Duration::from_millis(100));
+        time.update();
+        assert_eq!(time.fixed_steps(), 0);
+        assert!((time.alpha() - 0.4).abs() < 1e-6);
+
+        clock.advance(Duration::from_millis(200));
+        time.update();
+        assert_eq!(time.fixed_steps(), 1);
+        assert!((time.alpha() - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fixed_steps_are_limited_by_max_substeps() {
+        let clock = ManualClock::new();
+        let mut time = DeltaTime::with_clock(clock.clone());
+        time.set_fixed_delta_time(0.01);
+        time.set_max_substeps(3);
+
+        clock.advance(Duration::from_millis(105));
+        time.update();
+        assert_eq!(time.fixed_steps(), 3);
+        // only the fraction of a step is kept, the rest of the frame is dropped
+        assert!((time.alpha() - 0.5).abs() < 1e-3);
+
+        time.update();
+        assert_eq!(time.fixed_steps(), 0);
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut time = DeltaTime::new();
+        time.set_max_delta_time(0.1);
+
+        time.replay(1.0);
+        assert_eq!(time.unscaled_delta_time(), 0.1);
+        assert_eq!(time.delta_time(), 0.1);
+        assert_eq!(time.raw_delta_time(), 1.0);
+    }
+
+    #[test]
+    fn scaled_and_paused() {
+        let mut time = DeltaTime::new();
+        time.set_time_scale(0.5);
+        time.replay(0.1);
+        assert_eq!(time.delta_time(), 0.05);
+        assert_eq!(time.unscaled_delta_time(), 0.1);
+
+        time.pause();
+        time.replay(0.1);
+        assert_eq!(time.delta_time(), 0.0);
+        assert_eq!(time.elapsed(), 0.05f32 as f64);
+        assert_eq!(time.frame_count(), 2);
+    }
+
+    #[test]
+    fn invalid_settings_are_ignored() {
+        let mut time = DeltaTime::new();
+        time.set_max_delta_time(0.1);
+        time.set_fixed_rate(50.0);
+        time.set_time_scale(2.0);
+
+        time.set_max_delta_time(0.0);
+        time.set_max_delta_time(f32::NAN);
+        time.set_fixed_rate(0.0);
+        time.set_fixed_rate(f32::INFINITY);
+        time.set_fixed_delta_time(-1.0);
+        time.set_time_scale(-1.0);
+        time.set_time_scale(f32::NAN);
+
+        assert_eq!(time.max_delta_time(), 0.1);
+        assert_eq!(time.fixed_delta_time(), 0.02);
+        assert_eq!(time.time_scale(), 2.0);
+
+        time.set_max_delta_time(f32::INFINITY);
+        assert_eq!(time.max_delta_time(), f32::INFINITY);
+    }
+}
@@ -0,0 +1,115 @@
+use winit::dpi::PhysicalSize;
+
+/// Read-only information about the window, kept up to date by the RenoirApp. Unlike WindowOptions, changing this
+/// does nothing to the window.
+pub struct WindowInfo {
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    resized: bool,
+}
+
+impl WindowInfo {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// The size of the framebuffer in physical pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.size.width, self.size.height)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.size.width as f32 / self.size.height.max(1) as f32
+    }
+
+    /// The ratio of physical pixels to logical pixels, e.g. 2.0 on most HiDPI displays
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// True for the first frame after the framebuffer size or scale factor has changed
+    pub fn resized(&self) -> bool {
+        self.resized
+    }
+
+    pub(crate) fn set_size(&mut self, size: PhysicalSize<u32>) {
+        if size != self.size {
+            self.size = size;
+            self.resized = true;
+        }
+    }
+
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        if scale_factor != self.scale_factor {
+            self.scale_factor = scale_factor;
+            self.resized = true;
+        }
+    }
+
+    // called after the run_fn, same as RenoirInput::update
+    pub(crate) fn update(&mut self) {
+        self.resized = false;
+    }
+}
+
+impl Default for WindowInfo {
+    fn default() -> Self {
+        Self {
+            size: PhysicalSize::new(0, 0),
+            scale_factor: 1.0,
+            resized: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resized_only_lasts_one_frame() {
+        let mut window = WindowInfo::new();
+        window.set_size(PhysicalSize::new(800, 600));
+        assert!(window.resized());
+        assert_eq!(window.size(), (800, 600));
+
+        window.update();
+        assert!(!window.resized());
+    }
+
+    #[test]
+    fn setting_the_same_size_isnt_a_resize() {
+        let mut window = WindowInfo::new();
+        window.set_size(PhysicalSize::new(800, 600));
+        window.set_scale_factor(2.0);
+        window.update();
+
+        window.set_size(PhysicalSize::new(800, 600));
+        window.set_scale_factor(2.0);
+        assert!(!window.resized());
+
+        window.set_scale_factor(1.5);
+        assert!(window.resized());
+        assert_eq!(window.scale_factor(), 1.5);
+    }
+
+    #[test]
+    fn aspect_of_a_minimized_window() {
+        let mut window = WindowInfo::new();
+        window.set_size(PhysicalSize::new(800, 0));
+        assert!(window.aspect().is_finite());
+
+        window.set_size(PhysicalSize::new(800, 400));
+        assert_eq!(window.aspect(), 2.0);
+    }
+}
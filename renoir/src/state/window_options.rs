@@ -4,6 +4,8 @@ pub struct WindowOptions {
     pub fullscreen: bool,
     pub grab_cursor: bool,
     pub show_cursor: bool,
+    /// Lets an IME (input method editor) compose text for the window, turn this on while a text box is focused
+    pub allow_ime: bool,
 }
 
 impl WindowOptions {
@@ -13,8 +15,10 @@ impl WindowOptions {
         }
     }
 
-    // applies own window settings to a winit window, this is called from the main loop only
-    pub(crate) fn apply_to(&self, window: &Window) {
+    // applies own window settings to a winit window, this is called from the main loop only. `ime_allowed` is whatever
+    // allow_ime was last applied as (None for a new window), as allowing IME again resets a composition that's in
+    // progress on some platforms
+    pub(crate) fn apply_to(&self, window: &Window, ime_allowed: &mut Option<bool>) {
         window.set_fullscreen(match self.fullscreen {
             true => Some(winit::window::Fullscreen::Borderless(None)),
             false => None,
@@ -26,6 +30,10 @@ impl WindowOptions {
         });
 
         window.set_cursor_visible(self.show_cursor);
+        if *ime_allowed != Some(self.allow_ime) {
+            window.set_ime_allowed(self.allow_ime);
+            *ime_allowed = Some(self.allow_ime);
+        }
     }
 
     pub fn set_options(&mut self, options: Self) {
@@ -39,6 +47,7 @@ impl Default for WindowOptions {
             fullscreen: true,
             grab_cursor: true,
             show_cursor: false,
+            allow_ime: false,
         }
     }
 }